@@ -1,8 +1,9 @@
-use std::io::SeekFrom;
-use std::net::{Ipv4Addr, Ipv6Addr};
-use std::str::FromStr;
-use regex::Regex;
-use super::encoder::{Decoder, Encoder};
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use core::net::{Ipv4Addr, Ipv6Addr};
+use core::str::FromStr;
+use super::encoder::{normalize, percent_decode, percent_encode, Allowed, Decoder, Encoder, SUB_DELIMS, UNRESERVED};
 use super::Parser;
 use super::error::{Error, Result};
 
@@ -11,128 +12,354 @@ pub struct Authority {
     user_info: Option<UserInfo>,
     host: Host,
     port: Option<Port>,
+    // 有 ':' 但 port 为空, 如 "host:"
+    empty_port: bool,
+    serialized: String,
 }
 
 impl Authority {
     pub fn new(host: Host) -> Self {
-        Authority {
+        let mut aut = Authority {
             user_info: None,
             host,
             port: None,
-        }
+            empty_port: false,
+            serialized: String::new(),
+        };
+        aut.serialize();
+        aut
     }
 
     pub fn set_userinfo(&mut self, info: UserInfo) -> &mut Self {
         self.user_info = Some(info);
+        self.serialize();
+        self
+    }
+
+    pub fn set_host(&mut self, host: Host) -> &mut Self {
+        self.host = host;
+        self.serialize();
         self
     }
 
     pub fn set_port(&mut self, port: Port) -> &mut Self {
         self.port = Some(port);
+        self.empty_port = false;
+        self.serialize();
+        self
+    }
+
+    // 保留 ':' 分隔符但不带端口号
+    pub fn set_empty_port(&mut self) -> &mut Self {
+        self.port = None;
+        self.empty_port = true;
+        self.serialize();
+        self
+    }
+
+    pub fn clear_userinfo(&mut self) -> &mut Self {
+        self.user_info = None;
+        self.serialize();
+        self
+    }
+
+    pub fn clear_port(&mut self) -> &mut Self {
+        self.port = None;
+        self.empty_port = false;
+        self.serialize();
         self
     }
+
+    pub fn user_info(&self) -> Option<&UserInfo> {
+        self.user_info.as_ref()
+    }
+
+    pub fn host(&self) -> &Host {
+        &self.host
+    }
+
+    pub fn port(&self) -> Option<&Port> {
+        self.port.as_ref()
+    }
+
+    pub fn has_empty_port(&self) -> bool {
+        self.empty_port
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.serialized.is_empty()
+    }
+
+    pub fn as_str(&self) -> &str {
+        self.serialized.as_str()
+    }
+
+    pub fn redacted(&self) -> Self {
+        let mut aut = self.clone();
+        aut.user_info = self.user_info.as_ref().map(|u| u.redacted());
+        aut.serialize();
+        aut
+    }
+
+    // 空的 userinfo, host, port 与不存在的情况分别输出
+    fn serialize(&mut self) {
+        let mut buf = String::new();
+        if let Some(u) = &self.user_info {
+            buf.push_str(u.as_str());
+            buf.push('@');
+        }
+        buf.push_str(self.host.as_str());
+        if let Some(p) = &self.port {
+            buf.push(':');
+            buf.push_str(p.as_str());
+        } else if self.empty_port {
+            buf.push(':');
+        }
+        self.serialized = buf;
+    }
 }
 
 impl Parser for Authority {
+    // authority = [ userinfo "@" ] host [ ":" port ]
     fn decode(s: &str) -> Result<Self> {
-        let mut aut = Authority {
-            user_info: None,
-            host: Host::RegName(String::new()),
-            port: None,
+        let mut aut = Authority::new(Host::reg_name(""));
+        // userinfo 与 host 中都不能出现 '@', 以第一个为准
+        let rest = match s.split_once('@') {
+            Some((u, rest)) => {
+                aut.user_info = Some(UserInfo::decode(u)?);
+                rest
+            }
+            None => s,
         };
-        let mut rest = s.to_owned();
-        if let Some(uinfo_index) = rest.find('@') {
-            let right = rest.split_off(uinfo_index);
-            let u = UserInfo::decode(&rest)?;
-            aut.user_info = Some(u.clone());
-            rest = right;
-        }
-        if let Some(port_index) = rest.rfind(':') {
-            let right = rest.split_off(port_index);
-            let right = right.trim_start_matches(":");
-            let u = Port::decode(&right)?;
-            aut.port = Some(u.clone());
-        }
-        let rest = rest.trim_start_matches("@");
-        let u = Host::decode(rest)?;
-        aut.host = u.clone();
+        let (host, port) = if rest.starts_with('[') {
+            let end = match rest.find(']') {
+                Some(i) => i + 1,
+                None => return Err(Error::Decode(format!("ip-literal host is not closed by \']\', \'{}\'", s))),
+            };
+            match &rest[end..] {
+                "" => (&rest[..end], None),
+                p if p.starts_with(':') => (&rest[..end], Some(&p[1..])),
+                _ => return Err(Error::Decode(format!("unexpected characters after ip-literal host, \'{}\'", s))),
+            }
+        } else {
+            match rest.split_once(':') {
+                Some((h, p)) => (h, Some(p)),
+                None => (rest, None),
+            }
+        };
+        aut.host = Host::decode(host)?;
+        // port 可以为空
+        match port {
+            Some("") => aut.empty_port = true,
+            Some(p) => aut.port = Some(Port::decode(p)?),
+            None => {}
+        }
+        aut.serialize();
         Ok(aut)
     }
 
     fn encode(&self) -> Result<String> {
-        let mut buf = String::new();
-        let mut uinfo = String::new();
-        let mut host = String::new();
-        let mut port = String::new();
-        if let Some(c) = &self.user_info {
-            uinfo = c.encode()?;
-        }
-        host = self.host.encode()?;
-        if let Some(c) = &self.port {
-            port = c.encode()?;
-        }
-        if host.len() > 0 {
-            if uinfo.len() > 0 {
-                buf.push_str(format!("{}@", uinfo).as_str())
-            }
-            buf.push_str(host.as_str());
-            if port.len() > 0 {
-                buf.push_str(format!(":{}", port).as_str())
-            }
-        }
-        Ok(buf)
+        Ok(self.serialized.clone())
     }
 }
 
+impl_str_conv!(Authority);
+
 #[derive(Debug, Clone)]
-pub struct UserInfo(String);
+pub struct UserInfo {
+    username: String,
+    password: Option<String>,
+    serialized: String,
+}
 
 impl UserInfo {
+    // 以第一个 ':' 分隔用户名与密码
     pub fn new(s: &str) -> Self {
-        UserInfo(s.to_string())
+        match s.split_once(':') {
+            Some((u, p)) => UserInfo::with_password(u, p),
+            None => UserInfo::build(s.to_string(), None),
+        }
+    }
+
+    pub fn with_password(username: &str, password: &str) -> Self {
+        UserInfo::build(username.to_string(), Some(password.to_string()))
+    }
+
+    pub fn username(&self) -> &str {
+        self.username.as_str()
+    }
+
+    pub fn password(&self) -> Option<&str> {
+        self.password.as_deref()
+    }
+
+    pub fn set_username(&mut self, username: &str) -> &mut Self {
+        self.username = username.to_string();
+        self.serialize();
+        self
+    }
+
+    pub fn set_password(&mut self, password: &str) -> &mut Self {
+        self.password = Some(password.to_string());
+        self.serialize();
+        self
+    }
+
+    pub fn clear_password(&mut self) -> &mut Self {
+        self.password = None;
+        self.serialize();
+        self
+    }
+
+    pub fn as_str(&self) -> &str {
+        self.serialized.as_str()
+    }
+
+    // 用掩码替换密码, 用户名保持不变
+    pub fn redacted(&self) -> Self {
+        let password = self.password.as_ref().map(|_| crate::REDACTED.to_string());
+        UserInfo::build(self.username.clone(), password)
+    }
+
+    fn build(username: String, password: Option<String>) -> Self {
+        let mut u = UserInfo {
+            username,
+            password,
+            serialized: String::new(),
+        };
+        u.serialize();
+        u
+    }
+
+    fn serialize(&mut self) {
+        // 用户名中的 ':' 需要编码, 密码中的可以保留
+        let mut allowed = Allowed::new();
+        allowed.set_unreserved().set_subdelims();
+        let mut s = percent_encode(self.username.as_str(), &allowed);
+        if let Some(p) = &self.password {
+            allowed.set(vec![b':']);
+            s.push(':');
+            s.push_str(percent_encode(p.as_str(), &allowed).as_str());
+        }
+        self.serialized = s;
     }
+
 }
 
 impl Parser for UserInfo {
     fn decode(s: &str) -> Result<Self> {
-        let mut dec = Decoder::new(s);
-        dec.set_decode_pct();
-        dec.allowed().set_unreserved().set_subdelims().set(vec![b':']);
-        let s = dec.decode()?;
-        Ok(UserInfo(s))
+        // 先分隔再解码, 用户名中的 %3A 不会被当作分隔符
+        let (u, p) = match s.split_once(':') {
+            Some((u, p)) => (u, Some(p)),
+            None => (s, None),
+        };
+        let mut allowed = Allowed::new();
+        allowed.set_unreserved().set_subdelims();
+        let mut serialized = normalize(u, &allowed)?;
+        let username = percent_decode(serialized.as_str());
+        let password = match p {
+            Some(p) => {
+                allowed.set(vec![b':']);
+                let p = normalize(p, &allowed)?;
+                serialized.push(':');
+                serialized.push_str(p.as_str());
+                Some(percent_decode(p.as_str()))
+            }
+            None => None,
+        };
+        Ok(UserInfo {
+            username,
+            password,
+            serialized,
+        })
     }
 
     fn encode(&self) -> Result<String> {
-        let mut enc = Encoder::new(self.0.as_str());
-        enc.set_encode_pct();
-        enc.allowed().set_unreserved().set_subdelims().set(vec![b':']);
-        let s = enc.encode()?;
-        Ok(s)
+        Ok(self.serialized.clone())
     }
 }
 
-#[derive(Debug, Clone)]
-pub enum Host {
+impl_str_conv!(UserInfo);
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum HostKind {
     IPLiteral(String),
     IPv4Addr(Ipv4Addr),
     RegName(String),
 }
 
+#[derive(Debug, Clone)]
+pub struct Host {
+    kind: HostKind,
+    serialized: String,
+}
+
 impl Host {
+    pub fn new(kind: HostKind) -> Result<Self> {
+        match kind {
+            HostKind::IPLiteral(lit) => Host::ip_literal(lit.as_str()),
+            HostKind::IPv4Addr(v4addr) => Ok(Host::ipv4(v4addr)),
+            HostKind::RegName(regname) => Ok(Host::reg_name(regname.as_str())),
+        }
+    }
+
+    // 参数为未编码的原文
+    pub fn reg_name(s: &str) -> Self {
+        let mut allowed = Allowed::new();
+        allowed.set_unreserved().set_subdelims();
+        Host {
+            serialized: percent_encode(s, &allowed),
+            kind: HostKind::RegName(s.to_string()),
+        }
+    }
+
+    pub fn ipv4(addr: Ipv4Addr) -> Self {
+        Host {
+            kind: HostKind::IPv4Addr(addr),
+            serialized: addr.to_string(),
+        }
+    }
+
+    pub fn ipv6(addr: Ipv6Addr) -> Self {
+        Host {
+            kind: HostKind::IPLiteral(addr.to_string()),
+            serialized: format!("[{}]", addr),
+        }
+    }
+
+    // 参数为不带方括号的 ipv6 地址或 ipvfuture
+    pub fn ip_literal(s: &str) -> Result<Self> {
+        let lit = Host::encode_ipliteral(s)?;
+        Ok(Host {
+            serialized: format!("[{}]", lit),
+            kind: HostKind::IPLiteral(lit),
+        })
+    }
+
+    pub fn kind(&self) -> &HostKind {
+        &self.kind
+    }
+
+    pub fn as_str(&self) -> &str {
+        self.serialized.as_str()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.serialized.is_empty()
+    }
+
     fn decode_ipliteral(s: &str) -> Result<Host> {
         let s = &s[1..s.len() - 1];
-        if s.len() == 0 {
+        if s.is_empty() {
             return Err(Error::Encode("invalid host string, is cannot be empty".to_string()));
         }
         // ipv6地址
         if let Ok(v6addr) = Ipv6Addr::from_str(s) {
-            return Ok(Host::IPLiteral(v6addr.to_string()));
+            return Ok(Host::ipv6(v6addr));
         }
         // ipvfuture
-        let ipv_future: Regex =
-            Regex::new(r#"v[[:xdigit:]]+\.([[:alnum:]]|[!$&'\(\)*+,;=:])+"#)?;
-        if ipv_future.is_match(s) {
-            return Ok(Host::IPLiteral(s.to_string()));
+        if is_ipv_future(s) {
+            return Host::ip_literal(s);
         }
         Err(Error::Encode("not a valid ip-literal string".to_string()))
     }
@@ -141,77 +368,101 @@ impl Host {
         if let Ok(v6addr) = Ipv6Addr::from_str(s) {
             return Ok(v6addr.to_string());
         }
-        let ipv_future: Regex =
-            Regex::new(r#"v[[:xdigit:]]+\.([[:alnum:]]|[!$&'\(\)*+,;=:])+"#)?;
-        if ipv_future.is_match(s) {
+        if is_ipv_future(s) {
             return Ok(s.to_string());
         }
         Err(Error::Encode(format!("invalid ip literal string,\'{}\'", s)))
     }
 
     fn decode_reg_name(s: &str) -> Result<Host> {
-        let mut dec = Decoder::new(s);
-        dec.allow_empty();
-        dec.set_decode_pct();
-        dec.allowed().set_unreserved().set_subdelims();
-        match dec.decode() {
-            Ok(s) => Ok(Host::RegName(s)),
-            Err(e) => Err(Error::Encode(format!("not a valid reg-name host, err={}", e))),
+        let mut allowed = Allowed::new();
+        allowed.set_unreserved().set_subdelims();
+        match normalize(s, &allowed) {
+            Ok(serialized) => Ok(Host {
+                kind: HostKind::RegName(percent_decode(serialized.as_str())),
+                serialized,
+            }),
+            Err(e) => Err(Error::Decode(format!("not a valid reg-name host, err={}", e))),
         }
     }
+}
 
-    fn encode_reg_name(s: &str) -> Result<String> {
-        let mut enc = Encoder::new(s);
-        enc.allow_empty();
-        enc.allowed().set_unreserved().set_subdelims();
-        enc.set_encode_pct();
-        enc.encode()
+// IPvFuture = "v" 1*HEXDIG "." 1*( unreserved / sub-delims / ":" ), 需完整匹配
+pub(crate) fn is_ipv_future(s: &str) -> bool {
+    let bs = s.as_bytes();
+    // 字面量 "v" 不区分大小写
+    if !bs.first().is_some_and(|c| c.eq_ignore_ascii_case(&b'v')) {
+        return false;
     }
+    let hex = bs[1..].iter().take_while(|c| c.is_ascii_hexdigit()).count();
+    if hex == 0 || bs.get(1 + hex) != Some(&b'.') {
+        return false;
+    }
+    let rest = &bs[2 + hex..];
+    let allowed = UNRESERVED.union(SUB_DELIMS).with(b":");
+    !rest.is_empty() && rest.iter().all(|c| allowed.contains(*c))
 }
 
 impl Parser for Host {
     fn decode(s: &str) -> Result<Host> {
-        let sbytes = s.as_bytes();
         // IPLiteral
-        if sbytes.len() > 2 && sbytes[0] == b'[' && sbytes[sbytes.len() - 1] == b']' {
+        if s.starts_with('[') {
+            if s.len() < 2 || !s.ends_with(']') {
+                return Err(Error::Decode(format!("ip-literal host must be enclosed in brackets, \'{}\'", s)));
+            }
             return Host::decode_ipliteral(s);
         }
         // IPv4Addr
         if let Ok(v4addr) = Ipv4Addr::from_str(s) {
-            return Ok(Host::IPv4Addr(v4addr));
+            return Ok(Host::ipv4(v4addr));
         }
         // RegName
-        if let Ok(rname) = Host::decode_reg_name(s) {
-            return Ok(rname);
-        }
-        Err(Error::Encode(format!("not a valid host string \'{}\'", s)))
+        Host::decode_reg_name(s)
     }
 
     fn encode(&self) -> Result<String> {
-        match self {
-            Host::IPLiteral(lit) => Host::encode_ipliteral(lit.as_str()),
-            Host::IPv4Addr(v4addr) => Ok(v4addr.to_string()),
-            Host::RegName(regname) => Host::encode_reg_name(regname),
-        }
+        Ok(self.serialized.clone())
     }
 }
 
+impl_str_conv!(Host);
+
 #[derive(Debug, Clone)]
-pub struct Port(u16);
+pub struct Port {
+    port: u16,
+    serialized: String,
+}
 
 impl Port {
     pub fn new(p: u16) -> Self {
-        Port(p)
+        Port {
+            port: p,
+            serialized: p.to_string(),
+        }
+    }
+
+    pub fn as_u16(&self) -> u16 {
+        self.port
+    }
+
+    pub fn as_str(&self) -> &str {
+        self.serialized.as_str()
     }
 }
 
 impl Parser for Port {
     fn decode(s: &str) -> Result<Self> {
+        // u16::from_str 会接受 "+80"
+        if s.is_empty() || !s.bytes().all(|c| c.is_ascii_digit()) {
+            return Err(Error::Decode(format!("port must be digits, \'{}\'", s)));
+        }
         let port = u16::from_str(s)?;
-        Ok(Port(port))
+        Ok(Port::new(port))
     }
 
     fn encode(&self) -> Result<String> {
-        Ok(self.0.to_string())
+        Ok(self.serialized.clone())
     }
-}
\ No newline at end of file
+}
+
+impl_str_conv!(Port);
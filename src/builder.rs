@@ -0,0 +1,221 @@
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+use super::authority::Authority;
+use super::error::{Error, Result};
+use super::fragment::Fragment;
+use super::path::{Path, PathAbempty, PathAbsolute, PathNoScheme, PathRootless};
+use super::query::Query;
+use super::rpart::RPart;
+use super::scheme::Scheme;
+use super::uri::URI;
+
+#[derive(Debug, Clone, Default)]
+pub struct Builder {
+    scheme: Option<String>,
+    user_info: Option<String>,
+    host: Option<String>,
+    port: Option<u16>,
+    path: Option<String>,
+    query: Option<String>,
+    query_pairs: Vec<(String, String)>,
+    fragment: Option<String>,
+}
+
+impl Builder {
+    pub fn new() -> Self {
+        Builder::default()
+    }
+
+    pub fn scheme(&mut self, s: &str) -> &mut Self {
+        self.scheme = Some(s.to_string());
+        self
+    }
+
+    pub fn user_info(&mut self, s: &str) -> &mut Self {
+        self.user_info = Some(s.to_string());
+        self
+    }
+
+    pub fn host(&mut self, s: &str) -> &mut Self {
+        self.host = Some(s.to_string());
+        self
+    }
+
+    pub fn port(&mut self, port: u16) -> &mut Self {
+        self.port = Some(port);
+        self
+    }
+
+    pub fn path(&mut self, s: &str) -> &mut Self {
+        self.path = Some(s.to_string());
+        self
+    }
+
+    // 编码后的完整 query, 可以为空字符串
+    pub fn query(&mut self, s: &str) -> &mut Self {
+        self.query = Some(s.to_string());
+        self
+    }
+
+    // 参数为未编码的原文, 同一个键可多次添加
+    pub fn query_pair(&mut self, key: &str, val: &str) -> &mut Self {
+        self.query_pairs.push((key.to_string(), val.to_string()));
+        self
+    }
+
+    pub fn fragment(&mut self, s: &str) -> &mut Self {
+        self.fragment = Some(s.to_string());
+        self
+    }
+
+    // 各部分在此处统一校验
+    pub fn build(&self) -> Result<URI> {
+        let mut u = URI::new();
+        if let Some(s) = &self.scheme {
+            u.set_scheme(s)?;
+        }
+        match &self.host {
+            Some(h) => {
+                u.set_host(h)?;
+            }
+            None if self.user_info.is_some() || self.port.is_some() => {
+                return Err(Error::Invalid("userinfo and port require a host".to_string()));
+            }
+            None => {}
+        }
+        if let Some(s) = &self.user_info {
+            u.set_userinfo(s)?;
+        }
+        if let Some(p) = self.port {
+            u.set_port(p)?;
+        }
+        if let Some(s) = &self.path {
+            u.set_path(s)?;
+        }
+        if let Some(s) = &self.query {
+            u.set_query(s)?;
+        }
+        for (k, v) in self.query_pairs.iter() {
+            u.append_query_pair(k, v)?;
+        }
+        if let Some(s) = &self.fragment {
+            u.set_fragment(s)?;
+        }
+        Ok(u)
+    }
+}
+
+// 类型状态 builder, 只有合法的组合才能通过编译, build 不会失败
+pub struct NoScheme;
+pub struct WithScheme;
+pub struct WithAuthority;
+pub struct WithPath;
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+impl sealed::Sealed for PathAbempty {}
+impl sealed::Sealed for PathAbsolute {}
+impl sealed::Sealed for PathRootless {}
+impl sealed::Sealed for PathNoScheme {}
+
+// 在状态 S 下允许使用的 path 类型
+pub trait PathFor<S>: sealed::Sealed + Into<Path> {}
+
+impl PathFor<NoScheme> for PathAbsolute {}
+impl PathFor<NoScheme> for PathNoScheme {}
+impl PathFor<WithScheme> for PathAbsolute {}
+impl PathFor<WithScheme> for PathRootless {}
+impl PathFor<WithScheme> for PathNoScheme {}
+impl PathFor<WithAuthority> for PathAbempty {}
+impl PathFor<WithAuthority> for PathAbsolute {}
+
+pub struct UriBuilder<S> {
+    scheme: Option<Scheme>,
+    rpart: RPart,
+    query: Option<Query>,
+    fragment: Option<Fragment>,
+    state: PhantomData<S>,
+}
+
+impl Default for UriBuilder<NoScheme> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl UriBuilder<NoScheme> {
+    pub fn new() -> Self {
+        UriBuilder {
+            scheme: None,
+            rpart: RPart::new(),
+            query: None,
+            fragment: None,
+            state: PhantomData,
+        }
+    }
+
+    pub fn scheme(self, scheme: Scheme) -> UriBuilder<WithScheme> {
+        let mut b = self.into_state();
+        b.scheme = Some(scheme);
+        b
+    }
+
+    pub fn authority(self, authority: Authority) -> UriBuilder<WithAuthority> {
+        let mut b = self.into_state();
+        b.rpart.set_authority(Some(authority));
+        b
+    }
+}
+
+impl UriBuilder<WithScheme> {
+    pub fn authority(self, authority: Authority) -> UriBuilder<WithAuthority> {
+        let mut b = self.into_state();
+        b.rpart.set_authority(Some(authority));
+        b
+    }
+}
+
+impl<S> UriBuilder<S> {
+    pub fn path<P: PathFor<S>>(self, path: P) -> UriBuilder<WithPath> {
+        let mut b = self.into_state();
+        let path: Path = path.into();
+        if !path.is_empty() {
+            b.rpart.set_path(Some(path));
+        }
+        b
+    }
+
+    pub fn query(mut self, query: Query) -> Self {
+        self.query = Some(query);
+        self
+    }
+
+    pub fn fragment(mut self, fragment: Fragment) -> Self {
+        self.fragment = Some(fragment);
+        self
+    }
+
+    pub fn build(self) -> URI {
+        let rpart = if self.rpart.authority().is_none() && self.rpart.path().is_none() {
+            None
+        } else {
+            Some(self.rpart)
+        };
+        let u = URI::from_parts(self.scheme, rpart, self.query, self.fragment);
+        debug_assert!(u.validate().is_ok());
+        u
+    }
+
+    fn into_state<T>(self) -> UriBuilder<T> {
+        UriBuilder {
+            scheme: self.scheme,
+            rpart: self.rpart,
+            query: self.query,
+            fragment: self.fragment,
+            state: PhantomData,
+        }
+    }
+}
@@ -0,0 +1,318 @@
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use super::authority::HostKind;
+use super::encoder::percent_decode;
+use super::error::{Error, Result};
+use super::origin::Origin;
+use super::scheme::default_port;
+use super::uri::URI;
+use super::Parser;
+
+// CSP3 中的 source expression, 如 "'self'", "https:", "*.example.com:*", "https://cdn.example/path/"
+#[derive(Debug, Clone)]
+pub struct SourceExpression {
+    kind: SourceKind,
+    serialized: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SourceKind {
+    // "'none'"
+    None,
+    // "'self'"
+    SelfOrigin,
+    // "*"
+    Wildcard,
+    // "https:", 保存为小写且不含 ':'
+    Scheme(String),
+    Host(HostSource),
+    // 其余带引号的关键字, 如 "'unsafe-inline'", "'nonce-...'", 不匹配任何 URL
+    // 不含引号, 前缀为小写, nonce 与 hash 的值区分大小写, 保持原样
+    Keyword(String),
+}
+
+// [scheme "://"] host [":" port] [path], host 与 scheme 保存为小写
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HostSource {
+    pub scheme: Option<String>,
+    // "*", "*.example.com" 或 "example.com"
+    pub host: String,
+    pub port: Option<SourcePort>,
+    pub path: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SourcePort {
+    Any,
+    Number(u16),
+}
+
+impl SourceExpression {
+    pub fn kind(&self) -> &SourceKind {
+        &self.kind
+    }
+
+    pub fn as_str(&self) -> &str {
+        self.serialized.as_str()
+    }
+
+    // CSP3 6.7.2.8 "does url match expression in origin", origin 为受保护资源的 origin
+    pub fn matches(&self, url: &URI, origin: &Origin) -> bool {
+        let scheme = match url.scheme() {
+            Some(s) => s.as_str().to_ascii_lowercase(),
+            None => return false,
+        };
+        match &self.kind {
+            SourceKind::None | SourceKind::Keyword(_) => false,
+            SourceKind::Wildcard => is_network_scheme(&scheme) || origin.scheme() == Some(scheme.as_str()),
+            SourceKind::Scheme(s) => scheme_part_matches(s, &scheme),
+            SourceKind::Host(h) => h.matches(url, &scheme, origin),
+            SourceKind::SelfOrigin => self_matches(url, &scheme, origin),
+        }
+    }
+
+    pub fn matches_str(&self, url: &str, origin: &Origin) -> Result<bool> {
+        Ok(self.matches(&URI::decode(url)?, origin))
+    }
+}
+
+impl HostSource {
+    fn matches(&self, url: &URI, scheme: &str, origin: &Origin) -> bool {
+        let host = match url.host() {
+            Some(h) if !h.is_empty() => h,
+            _ => return false,
+        };
+        // 没有 scheme 时使用受保护资源的 scheme
+        let expected = match self.scheme.as_deref().or(origin.scheme()) {
+            Some(s) => s,
+            None => return false,
+        };
+        if !scheme_part_matches(expected, scheme) {
+            return false;
+        }
+        let is_domain = matches!(host.kind(), HostKind::RegName(_));
+        if !host_part_matches(&self.host, host.as_str(), is_domain) {
+            return false;
+        }
+        if !self.port_matches(url, scheme) {
+            return false;
+        }
+        match &self.path {
+            Some(p) => path_part_matches(p, url.path().map(|p| p.as_str()).unwrap_or("")),
+            None => true,
+        }
+    }
+
+    fn port_matches(&self, url: &URI, scheme: &str) -> bool {
+        let actual = url.port().map(|p| p.as_u16()).or_else(|| default_port(scheme));
+        match self.port {
+            Some(SourcePort::Any) => true,
+            // 省略端口时只匹配 url scheme 的默认端口
+            None => url.port().is_none() || actual == default_port(scheme),
+            // 允许 80 升级到 443
+            Some(SourcePort::Number(p)) => actual == Some(p) || (p == 80 && actual == Some(443)),
+        }
+    }
+}
+
+// CSP3 6.7.2.9, 允许 http -> https 与 ws -> wss 的升级
+fn scheme_part_matches(expr: &str, scheme: &str) -> bool {
+    match (expr, scheme) {
+        (a, b) if a.eq_ignore_ascii_case(b) => true,
+        ("http", "https") | ("ws", "wss" | "http" | "https") | ("wss", "https") => true,
+        _ => false,
+    }
+}
+
+// CSP3 6.7.2.10, ip 地址不匹配任何 host-source, 只能由 'self' 或 "*" 匹配
+fn host_part_matches(pattern: &str, host: &str, is_domain: bool) -> bool {
+    if !is_domain {
+        return false;
+    }
+    if pattern == "*" {
+        return true;
+    }
+    if let Some(rest) = pattern.strip_prefix('*') {
+        // rest 以 '.' 开头, 不匹配域名自身
+        return host.len() > rest.len() && host[host.len() - rest.len()..].eq_ignore_ascii_case(rest);
+    }
+    pattern.eq_ignore_ascii_case(host.trim_end_matches('.'))
+}
+
+// CSP3 6.7.2.12, 以 '/' 结尾的路径按前缀匹配, 否则须完全相同
+fn path_part_matches(pattern: &str, path: &str) -> bool {
+    if pattern.is_empty() || (pattern == "/" && path.is_empty()) {
+        return true;
+    }
+    let exact = !pattern.ends_with('/');
+    let mut expected: Vec<&str> = pattern.split('/').collect();
+    let actual: Vec<&str> = path.split('/').collect();
+    if exact && expected.len() != actual.len() {
+        return false;
+    }
+    if !exact {
+        expected.pop();
+    }
+    if expected.len() > actual.len() {
+        return false;
+    }
+    expected.iter().zip(actual.iter()).all(|(e, a)| percent_decode(e) == percent_decode(a))
+}
+
+// CSP3 6.7.2.8 中 'self' 的规则, 同一 host 与端口时允许升级到安全的 scheme
+fn self_matches(url: &URI, scheme: &str, origin: &Origin) -> bool {
+    let target = url.origin();
+    if origin.is_same_origin(&target) {
+        return true;
+    }
+    let (o_scheme, o_host, o_port) = match origin {
+        Origin::Tuple { scheme, host, port } => (scheme.as_str(), host.as_str(), *port),
+        Origin::Opaque => return false,
+    };
+    let same_port = target.port() == Some(o_port)
+        || (default_port(o_scheme) == Some(o_port) && url.port().is_none_or(|p| default_port(scheme) == Some(p.as_u16())));
+    target.host() == Some(o_host)
+        && same_port
+        && (matches!(scheme, "https" | "wss") || (o_scheme == "http" && matches!(scheme, "http" | "ws")))
+}
+
+// CSP3 2.3.1 的 nonce-source 与 hash-source
+fn is_nonce_or_hash(prefix: &str) -> bool {
+    ["nonce", "sha256", "sha384", "sha512"].iter().any(|p| p.eq_ignore_ascii_case(prefix))
+}
+
+fn is_network_scheme(scheme: &str) -> bool {
+    matches!(scheme, "http" | "https" | "ws" | "wss")
+}
+
+fn is_scheme(s: &str) -> bool {
+    s.as_bytes().first().is_some_and(|c| c.is_ascii_alphabetic())
+        && s.bytes().all(|c| c.is_ascii_alphanumeric() || matches!(c, b'+' | b'-' | b'.'))
+}
+
+impl Parser for SourceExpression {
+    fn decode(s: &str) -> Result<Self> {
+        let invalid = |reason: &str| Error::Decode(format!("{} in source expression \'{}\'", reason, s));
+        let kind = if let Some(keyword) = s.strip_prefix('\'').and_then(|k| k.strip_suffix('\'')) {
+            match keyword.split_once('-') {
+                Some((prefix, value)) if is_nonce_or_hash(prefix) => {
+                    SourceKind::Keyword(format!("{}-{}", prefix.to_ascii_lowercase(), value))
+                }
+                _ => {
+                    let keyword = keyword.to_ascii_lowercase();
+                    match keyword.as_str() {
+                        "none" => SourceKind::None,
+                        "self" => SourceKind::SelfOrigin,
+                        "" => return Err(invalid("empty keyword")),
+                        _ => SourceKind::Keyword(keyword),
+                    }
+                }
+            }
+        } else if s == "*" {
+            SourceKind::Wildcard
+        } else if let Some(scheme) = s.strip_suffix(':').filter(|sch| is_scheme(sch)) {
+            SourceKind::Scheme(scheme.to_ascii_lowercase())
+        } else {
+            SourceKind::Host(parse_host_source(s).map_err(invalid)?)
+        };
+        Ok(SourceExpression {
+            kind,
+            serialized: s.to_string(),
+        })
+    }
+
+    fn encode(&self) -> Result<String> {
+        Ok(self.serialized.clone())
+    }
+}
+
+impl_str_conv!(SourceExpression);
+
+fn parse_host_source(s: &str) -> core::result::Result<HostSource, &'static str> {
+    let (scheme, rest) = match s.split_once("://") {
+        Some((sch, _)) if !is_scheme(sch) => return Err("invalid scheme"),
+        Some((sch, rest)) => (Some(sch.to_ascii_lowercase()), rest),
+        None => (None, s),
+    };
+    let (authority, path) = match rest.find('/') {
+        Some(i) => (&rest[..i], Some(&rest[i..])),
+        None => (rest, None),
+    };
+    let (host, port) = match authority.split_once(':') {
+        Some((h, p)) => (h, Some(p)),
+        None => (authority, None),
+    };
+    let labels = host.strip_prefix("*.").unwrap_or(host);
+    let valid_label = |l: &str| !l.is_empty() && l.bytes().all(|c| c.is_ascii_alphanumeric() || c == b'-');
+    if host != "*" && !labels.split('.').all(valid_label) {
+        return Err("invalid host");
+    }
+    let port = match port {
+        None => None,
+        Some("*") => Some(SourcePort::Any),
+        Some(p) if !p.is_empty() && p.bytes().all(|c| c.is_ascii_digit()) => {
+            Some(SourcePort::Number(p.parse().map_err(|_| "invalid port")?))
+        }
+        Some(_) => return Err("invalid port"),
+    };
+    if path.is_some_and(|p| p.contains([';', ',', '?', '#'])) {
+        return Err("invalid path");
+    }
+    Ok(HostSource {
+        scheme,
+        host: host.to_ascii_lowercase(),
+        port,
+        path: path.map(|p| p.to_string()),
+    })
+}
+
+// 指令值中以空白分隔的 source expression 列表
+#[derive(Debug, Clone)]
+pub struct SourceList {
+    sources: Vec<SourceExpression>,
+    serialized: String,
+}
+
+impl SourceList {
+    pub fn as_str(&self) -> &str {
+        self.serialized.as_str()
+    }
+
+    pub fn sources(&self) -> &[SourceExpression] {
+        &self.sources
+    }
+
+    pub fn len(&self) -> usize {
+        self.sources.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sources.is_empty()
+    }
+
+    // 任一表达式匹配即允许, 空列表与 'none' 不允许任何 URL
+    pub fn matches(&self, url: &URI, origin: &Origin) -> bool {
+        self.sources.iter().any(|e| e.matches(url, origin))
+    }
+
+    pub fn matches_str(&self, url: &str, origin: &Origin) -> Result<bool> {
+        Ok(self.matches(&URI::decode(url)?, origin))
+    }
+}
+
+impl Parser for SourceList {
+    fn decode(s: &str) -> Result<Self> {
+        let sources = s.split_ascii_whitespace().map(SourceExpression::decode).collect::<Result<Vec<_>>>()?;
+        Ok(SourceList {
+            serialized: sources.iter().map(|e| e.as_str()).collect::<Vec<_>>().join(" "),
+            sources,
+        })
+    }
+
+    fn encode(&self) -> Result<String> {
+        Ok(self.serialized.clone())
+    }
+}
+
+impl_str_conv!(SourceList);
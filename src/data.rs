@@ -0,0 +1,239 @@
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use super::encoder::{percent_decode, percent_decode_bytes, percent_encode, percent_encode_bytes, Allowed, PCHAR, UNRESERVED};
+use super::error::{Error, Result};
+use super::uri::URI;
+use super::Parser;
+
+// rfc2397, data:[<mediatype>][;base64],<data>
+#[derive(Debug, Clone)]
+pub struct DataUri {
+    // 小写, 为空表示省略, 即 text/plain;charset=US-ASCII
+    media_type: String,
+    params: Vec<(String, String)>,
+    base64: bool,
+    data: Vec<u8>,
+    serialized: String,
+}
+
+const DEFAULT_MEDIA_TYPE: &str = "text/plain";
+const DEFAULT_CHARSET: &str = "US-ASCII";
+const BASE64_CHARS: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+// 非 base64 数据中不编码的字符
+const DATA_CHARS: Allowed = PCHAR.with(b"/?");
+
+impl DataUri {
+    // 默认使用 base64 编码数据
+    pub fn new(media_type: &str, data: &[u8]) -> Result<Self> {
+        let mut d = DataUri {
+            media_type: String::new(),
+            params: Vec::new(),
+            base64: true,
+            data: data.to_vec(),
+            serialized: String::new(),
+        };
+        d.set_media_type(media_type)?;
+        Ok(d)
+    }
+
+    pub fn as_str(&self) -> &str {
+        self.serialized.as_str()
+    }
+
+    pub fn media_type(&self) -> &str {
+        if self.media_type.is_empty() {
+            DEFAULT_MEDIA_TYPE
+        } else {
+            self.media_type.as_str()
+        }
+    }
+
+    // 参数值已解码
+    pub fn params(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.params.iter().map(|(k, v)| (k.as_str(), v.as_str()))
+    }
+
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.params.iter().find(|(k, _)| k.eq_ignore_ascii_case(name)).map(|(_, v)| v.as_str())
+    }
+
+    // 省略媒体类型时为 US-ASCII
+    pub fn charset(&self) -> Option<&str> {
+        match self.param("charset") {
+            Some(c) => Some(c),
+            None if self.media_type.is_empty() => Some(DEFAULT_CHARSET),
+            None => None,
+        }
+    }
+
+    pub fn is_base64(&self) -> bool {
+        self.base64
+    }
+
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    pub fn into_data(self) -> Vec<u8> {
+        self.data
+    }
+
+    // 为空时表示省略媒体类型
+    pub fn set_media_type(&mut self, s: &str) -> Result<&mut Self> {
+        if !s.is_empty() && !is_media_type(s) {
+            return Err(Error::Invalid(format!("invalid media type \'{}\'", s)));
+        }
+        self.media_type = s.to_ascii_lowercase();
+        self.serialize();
+        Ok(self)
+    }
+
+    // 参数名不区分大小写, 已存在时替换
+    pub fn set_param(&mut self, name: &str, value: &str) -> Result<&mut Self> {
+        if !is_token(name) || name.eq_ignore_ascii_case("base64") {
+            return Err(Error::Invalid(format!("invalid parameter name \'{}\'", name)));
+        }
+        match self.params.iter_mut().find(|(k, _)| k.eq_ignore_ascii_case(name)) {
+            Some((_, v)) => *v = value.to_string(),
+            None => self.params.push((name.to_ascii_lowercase(), value.to_string())),
+        }
+        self.serialize();
+        Ok(self)
+    }
+
+    pub fn remove_param(&mut self, name: &str) -> &mut Self {
+        self.params.retain(|(k, _)| !k.eq_ignore_ascii_case(name));
+        self.serialize();
+        self
+    }
+
+    pub fn set_base64(&mut self, base64: bool) -> &mut Self {
+        self.base64 = base64;
+        self.serialize();
+        self
+    }
+
+    pub fn set_data(&mut self, data: &[u8]) -> &mut Self {
+        self.data = data.to_vec();
+        self.serialize();
+        self
+    }
+
+    pub fn to_uri(&self) -> Result<URI> {
+        URI::decode(self.as_str())
+    }
+
+    fn serialize(&mut self) {
+        let mut buf = String::with_capacity(16 + self.media_type.len() + self.data.len() * 4 / 3);
+        buf.push_str("data:");
+        buf.push_str(&self.media_type);
+        for (k, v) in &self.params {
+            buf.push(';');
+            buf.push_str(k);
+            buf.push('=');
+            buf.push_str(&percent_encode(v, &UNRESERVED));
+        }
+        buf.push(',');
+        if self.base64 {
+            buf.insert_str(buf.len() - 1, ";base64");
+            base64_encode(&self.data, &mut buf);
+        } else {
+            buf.push_str(&percent_encode_bytes(&self.data, &DATA_CHARS));
+        }
+        self.serialized = buf;
+    }
+}
+
+impl TryFrom<&URI> for DataUri {
+    type Error = Error;
+
+    fn try_from(u: &URI) -> Result<Self> {
+        DataUri::decode(u.as_str())
+    }
+}
+
+impl Parser for DataUri {
+    fn decode(s: &str) -> Result<Self> {
+        URI::validate_str(s)?;
+        let rest = match s.get(..5) {
+            Some(p) if p.eq_ignore_ascii_case("data:") => &s[5..],
+            _ => return Err(Error::Decode(format!("not a data uri, \'{}\'", s))),
+        };
+        let (header, payload) = rest
+            .split_once(',')
+            .ok_or_else(|| Error::Decode(format!("missing \',\' in data uri \'{}\'", s)))?;
+        let mut pieces: Vec<&str> = header.split(';').collect();
+        let base64 = pieces.len() > 1 && pieces.last().is_some_and(|p| p.eq_ignore_ascii_case("base64"));
+        if base64 {
+            pieces.pop();
+        }
+        let mut d = DataUri::new("", &[])?;
+        d.set_media_type(&percent_decode(pieces[0]))?;
+        for p in &pieces[1..] {
+            let (k, v) = p
+                .split_once('=')
+                .ok_or_else(|| Error::Decode(format!("invalid parameter \'{}\' in data uri \'{}\'", p, s)))?;
+            d.set_param(k, &percent_decode(v))?;
+        }
+        let bytes = percent_decode_bytes(payload);
+        d.data = if base64 {
+            base64_decode(&bytes).ok_or_else(|| Error::Decode(format!("invalid base64 data in \'{}\'", s)))?
+        } else {
+            bytes
+        };
+        d.base64 = base64;
+        d.serialize();
+        Ok(d)
+    }
+
+    fn encode(&self) -> Result<String> {
+        Ok(self.serialized.clone())
+    }
+}
+
+impl_str_conv!(DataUri);
+
+// rfc2045 5.1 的 token
+fn is_token(s: &str) -> bool {
+    !s.is_empty() && s.bytes().all(|c| c.is_ascii_graphic() && !b"()<>@,;:\\\"/[]?=".contains(&c))
+}
+
+fn is_media_type(s: &str) -> bool {
+    matches!(s.split_once('/'), Some((t, sub)) if is_token(t) && is_token(sub))
+}
+
+// 标准字母表, 带 '=' 填充
+fn base64_encode(data: &[u8], buf: &mut String) {
+    for chunk in data.chunks(3) {
+        let n = chunk.iter().enumerate().fold(0u32, |n, (i, b)| n | (*b as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                buf.push(BASE64_CHARS[(n >> (18 - 6 * i) & 0x3F) as usize] as char);
+            } else {
+                buf.push('=');
+            }
+        }
+    }
+}
+
+// 忽略空白, 填充可以省略
+fn base64_decode(s: &[u8]) -> Option<Vec<u8>> {
+    let digits: Vec<u8> = s.iter().copied().filter(|c| !c.is_ascii_whitespace()).collect();
+    let end = digits.iter().rposition(|c| *c != b'=').map_or(0, |i| i + 1);
+    if digits.len() - end > 2 || (digits.len() != end && !digits.len().is_multiple_of(4)) || end % 4 == 1 {
+        return None;
+    }
+    let mut out = Vec::with_capacity(end * 3 / 4);
+    let (mut n, mut bits) = (0u32, 0);
+    for c in &digits[..end] {
+        let v = BASE64_CHARS.iter().position(|b| b == c)? as u32;
+        n = n << 6 | v;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((n >> bits) as u8);
+        }
+    }
+    Some(out)
+}
@@ -1,45 +1,84 @@
 #![allow(unused)]
 
-use core::str::utf8_char_width;
 use super::error::{Error, Result};
-use std::{collections::HashSet, str::Chars};
-use std::fmt::format;
-use std::slice::Iter;
+use alloc::fmt::format;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::slice::Iter;
+use core::str::Chars;
 
-pub struct Allowed(HashSet<u8>);
+// 允许的 ascii 字符集合, 以位图保存, 可在常量中构造
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Allowed(u128);
+
+pub const ALPHANUM: Allowed = Allowed::new().with_range(b'a', b'z').with_range(b'A', b'Z').with_range(b'0', b'9');
+pub const UNRESERVED: Allowed = ALPHANUM.with(b"-._~");
+pub const SUB_DELIMS: Allowed = Allowed::new().with(b"!$&'()*+,;=");
+pub const GEN_DELIMS: Allowed = Allowed::new().with(b":/?#[]@");
+// pchar 中除百分比编码外的字符
+pub const PCHAR: Allowed = UNRESERVED.union(SUB_DELIMS).with(b":@");
 
 impl Allowed {
-    pub fn new() -> Self {
-        Allowed(HashSet::new())
+    #[allow(clippy::new_without_default)]
+    pub const fn new() -> Self {
+        Allowed(0)
+    }
+
+    pub const fn with(self, chs: &[u8]) -> Self {
+        let mut bits = self.0;
+        let mut i = 0;
+        while i < chs.len() {
+            if chs[i] < 128 {
+                bits |= 1 << chs[i];
+            }
+            i += 1;
+        }
+        Allowed(bits)
+    }
+
+    pub const fn with_range(self, lo: u8, hi: u8) -> Self {
+        let mut bits = self.0;
+        let mut c = lo;
+        while c <= hi && c < 128 {
+            bits |= 1 << c;
+            c += 1;
+        }
+        Allowed(bits)
+    }
+
+    pub const fn union(self, other: Allowed) -> Self {
+        Allowed(self.0 | other.0)
     }
 
     pub fn set_unreserved(&mut self) -> &mut Self {
-        self.set_alphanum();
-        self.0.extend([b'-', b'.', b'_', b'~']);
+        *self = self.union(UNRESERVED);
         self
     }
 
     pub fn set_alphanum(&mut self) -> &mut Self {
-        self.0.extend(b'a'..=b'z');
-        self.0.extend(b'A'..=b'Z');
-        self.0.extend(b'0'..=b'9');
+        *self = self.union(ALPHANUM);
         self
     }
 
     pub fn set_subdelims(&mut self) -> &mut Self {
-        self.0.extend([b'!', b'$', b'&', b'\'', b'(', b')', b'*', b'+', b',', b';', b'=']);
+        *self = self.union(SUB_DELIMS);
         self
     }
 
     pub fn set_gendelims(&mut self) -> &mut Self {
-        self.0.extend([b':', b'/', b'?', b'#', b'[', b']', b'@']);
+        *self = self.union(GEN_DELIMS);
         self
     }
 
     pub fn set(&mut self, chs: Vec<u8>) -> &mut Self {
-        self.0.extend(chs);
+        *self = self.with(&chs);
         self
     }
+
+    pub const fn contains(&self, c: u8) -> bool {
+        c < 128 && self.0 & (1 << c) != 0
+    }
 }
 
 pub struct Decoder {
@@ -59,6 +98,7 @@ impl Decoder {
         }
     }
 
+    #[allow(clippy::len_zero)]
     pub fn decode(&self) -> Result<String> {
         let mut buf = Vec::<u8>::new();
         // 空判断
@@ -71,7 +111,7 @@ impl Decoder {
         let mut iter = self.data.as_bytes().iter();
         while let Some(item) = iter.next() {
             // 是否允许的字符
-            if self.allowed.0.contains(&item) {
+            if self.allowed.contains(*item) {
                 buf.push(*item);
                 continue;
             }
@@ -122,6 +162,7 @@ impl Encoder {
         }
     }
 
+    #[allow(clippy::len_zero)]
     pub fn encode(&self) -> Result<String> {
         let mut buf = Vec::<u8>::new();
         // 空判断
@@ -134,7 +175,7 @@ impl Encoder {
         let mut iter = self.data.as_bytes().iter();
         while let Some(item) = iter.next() {
             // 是否允许的字符
-            if self.allowed.0.contains(&item) {
+            if self.allowed.contains(*item) {
                 buf.push(*item);
                 continue;
             }
@@ -147,7 +188,7 @@ impl Encoder {
             }
             // 其它字符的百分比编码
             if self.encode_pct {
-                let s = format!("%{:X}", item);
+                let s = format!("%{:02X}", item);
                 buf.extend(s.as_bytes());
                 continue;
             }
@@ -182,7 +223,89 @@ impl Encoder {
     }
 }
 
+// 对未编码的原文做百分比编码, 不在允许集合中的字节(包括 '%')一律编码, 不会失败
+pub fn percent_encode(s: &str, allowed: &Allowed) -> String {
+    percent_encode_bytes(s.as_bytes(), allowed)
+}
+
+// 同上, 原文可以是任意字节
+pub fn percent_encode_bytes(bs: &[u8], allowed: &Allowed) -> String {
+    let mut buf = String::with_capacity(bs.len());
+    for item in bs {
+        if allowed.contains(*item) {
+            buf.push(*item as char);
+        } else {
+            buf.push_str(format!("%{:02X}", item).as_str());
+        }
+    }
+    buf
+}
+
+// 校验已编码的字符串并规范化, rfc3986 6.2.2
+// 十六进制统一为大写, 编码过的非保留字符还原, 其余编码保持不变
+pub fn normalize(s: &str, allowed: &Allowed) -> Result<String> {
+    let bs = s.as_bytes();
+    let mut buf = String::with_capacity(s.len());
+    let mut i = 0;
+    while i < bs.len() {
+        let c = bs[i];
+        if c == b'%' {
+            let hex = match bs.get(i + 1..i + 3) {
+                Some(h) if h.iter().all(|c| c.is_ascii_hexdigit()) => h,
+                _ => return Err(Error::Decode(format!("invalid percent encoding at {} in \'{}\'", i, s))),
+            };
+            let v = (hex_value(hex[0]) << 4) | hex_value(hex[1]);
+            if v.is_ascii_alphanumeric() || matches!(v, b'-' | b'.' | b'_' | b'~') {
+                buf.push(v as char);
+            } else {
+                buf.push_str(format!("%{:02X}", v).as_str());
+            }
+            i += 3;
+            continue;
+        }
+        if !allowed.contains(c) {
+            let ch = s[i..].chars().next().unwrap_or('?');
+            return Err(Error::Decode(format!("invalid character \'{}\' at {} in \'{}\'", ch, i, s)));
+        }
+        buf.push(c as char);
+        i += 1;
+    }
+    Ok(buf)
+}
+
+// 百分比解码, 结果不是合法 utf-8 时用 U+FFFD 替换
+pub fn percent_decode(s: &str) -> String {
+    String::from_utf8_lossy(&percent_decode_bytes(s)).into_owned()
+}
+
+// 百分比解码为原始字节, 不合法的编码保持原样
+pub fn percent_decode_bytes(s: &str) -> Vec<u8> {
+    let bs = s.as_bytes();
+    let mut buf = Vec::with_capacity(bs.len());
+    let mut i = 0;
+    while i < bs.len() {
+        if bs[i] == b'%' && i + 2 < bs.len() && bs[i + 1].is_ascii_hexdigit() && bs[i + 2].is_ascii_hexdigit() {
+            buf.push((hex_value(bs[i + 1]) << 4) | hex_value(bs[i + 2]));
+            i += 3;
+            continue;
+        }
+        buf.push(bs[i]);
+        i += 1;
+    }
+    buf
+}
+
+fn hex_value(c: u8) -> u8 {
+    match c {
+        b'0'..=b'9' => c - b'0',
+        b'a'..=b'f' => c - b'a' + 10,
+        b'A'..=b'F' => c - b'A' + 10,
+        _ => 0,
+    }
+}
+
 // 检查百分比编码
+#[allow(clippy::needless_range_loop)]
 fn pct_encode_check(iter: &mut Iter<u8>) -> Result<[u8; 2]> {
     let mut buf: [u8; 2] = [0; 2];
     for i in 0..=1 {
@@ -194,7 +317,7 @@ fn pct_encode_check(iter: &mut Iter<u8>) -> Result<[u8; 2]> {
                 ));
             }
         };
-        if !(matches!(sub,b'0'..=b'9'|b'A'..=b'F')) {
+        if !sub.is_ascii_hexdigit() {
             return Err(Error::Encode(
                 "percent encoding char should be a hex digit".to_string(),
             ));
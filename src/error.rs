@@ -1,19 +1,22 @@
-use regex::Error as RegexError;
-use std::{array::TryFromSliceError, io, num::ParseIntError, string::FromUtf8Error};
-use std::fmt::{Display, Formatter, Write};
+use alloc::string::{FromUtf8Error, String, ToString};
+use core::{array::TryFromSliceError, num::ParseIntError};
+use core::fmt::{Display, Formatter, Write};
+#[cfg(feature = "std")]
+use std::io;
 
-pub type Result<T> = std::result::Result<T, Error>;
+pub type Result<T> = core::result::Result<T, Error>;
 
 #[derive(Debug)]
 pub enum Error {
     TryFromSlice(TryFromSliceError),
     FromUtf8(FromUtf8Error),
-    Regex(RegexError),
-    IO(std::io::Error),
+    #[cfg(feature = "std")]
+    IO(io::Error),
     ParseInt(ParseIntError),
     Encode(String),
     Decode(String),
     Path(String),
+    Invalid(String),
     Unknown,
 }
 
@@ -35,12 +38,7 @@ impl From<TryFromSliceError> for Error {
     }
 }
 
-impl From<RegexError> for Error {
-    fn from(value: RegexError) -> Self {
-        Error::Regex(value)
-    }
-}
-
+#[cfg(feature = "std")]
 impl From<io::Error> for Error {
     fn from(value: io::Error) -> Self {
         Error::IO(value)
@@ -48,17 +46,18 @@ impl From<io::Error> for Error {
 }
 
 impl Display for Error {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         let mut msg = String::new();
         match self {
             Error::TryFromSlice(e) => msg = e.to_string(),
             Error::FromUtf8(e) => msg = e.to_string(),
-            Error::Regex(e) => msg = e.to_string(),
             Error::ParseInt(e) => msg = e.to_string(),
+            #[cfg(feature = "std")]
             Error::IO(e) => msg = e.to_string(),
             Error::Encode(e) => msg = e.to_string(),
             Error::Decode(e) => msg = e.to_string(),
             Error::Path(e) => msg = e.to_string(),
+            Error::Invalid(e) => msg = e.to_string(),
             Error::Unknown => msg = "unknown".to_string(),
         }
         f.write_str(msg.as_str())
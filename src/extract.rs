@@ -0,0 +1,370 @@
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use super::encoder::{percent_decode, Allowed, UNRESERVED};
+use super::error::{Error, Result};
+use super::template::{reserved_allowed, Expression, Modifier, Operator, Part, Template, Value, VarSpec, Vars};
+use super::uri::URI;
+
+// 单个表达式的匹配结果
+enum Bind {
+    Fail,
+    Vars(Vec<(String, Value)>),
+    // 可以有多种绑定方式, 如 {x,y} 只匹配到一个值
+    Ambiguous,
+}
+
+// 匹配到多个结果时停止搜索
+const MAX_FOUND: usize = 2;
+// 单个表达式最多匹配的字节数
+const MAX_EXPRESSION_LEN: usize = 4096;
+
+impl Template {
+    // 展开的逆过程, 未匹配返回 None, 有多种绑定方式时返回错误
+    // 与 uri 的规范形式比较, 结果中的值已百分比解码
+    pub fn extract(&self, uri: &URI) -> Result<Option<Vars>> {
+        self.extract_str(uri.as_str())
+    }
+
+    pub fn extract_str(&self, s: &str) -> Result<Option<Vars>> {
+        let parts = self.parts();
+        let mut search = Search {
+            parts,
+            s,
+            later: later_names(parts),
+            reach: reachable(parts, s),
+            memo: BTreeMap::new(),
+        };
+        match search.run(0, 0, &Vars::new()).as_slice() {
+            [] => Ok(None),
+            [Some(vars)] => Ok(Some(vars.clone())),
+            _ => Err(Error::Invalid(format!("ambiguous match of template \'{}\' against \'{}\'", self, s))),
+        }
+    }
+}
+
+// 至多 MAX_FOUND 种不同的绑定, None 表示有歧义的匹配
+type Found = Vec<Option<Vars>>;
+
+// 搜索各表达式所占的子串
+struct Search<'a> {
+    parts: &'a [Part],
+    s: &'a str,
+    // later[i] 为 parts[i..] 中出现的变量名
+    later: Vec<BTreeSet<String>>,
+    // reach[i][pos] 为 parts[i..] 只按字符集与字面量判断时能否从 pos 匹配到结尾
+    reach: Vec<Vec<bool>>,
+    // (部件序号, 位置) -> [(之后仍会用到的已绑定变量, parts[i..] 新绑定的变量)]
+    memo: BTreeMap<(usize, usize), Vec<(Vars, Found)>>,
+}
+
+impl Search<'_> {
+    // 返回 parts[i..] 从 pos 开始的至多 MAX_FOUND 种绑定, 成功与失败都会被记录
+    fn run(&mut self, i: usize, pos: usize, acc: &Vars) -> Found {
+        if !self.reach[i][pos] {
+            return Vec::new();
+        }
+        let part = match self.parts.get(i) {
+            Some(p) => p,
+            None => return alloc::vec![Some(Vars::new())],
+        };
+        // 同名变量会影响之后的匹配, 只有这些绑定相同时状态才等价
+        let key: Vars = acc.iter().filter(|(k, _)| self.later[i].contains(*k)).map(|(k, v)| (k.clone(), v.clone())).collect();
+        if let Some((_, found)) = self.memo.get(&(i, pos)).and_then(|seen| seen.iter().find(|(k, _)| *k == key)) {
+            return found.clone();
+        }
+        let found = self.step(part, i, pos, &key);
+        self.memo.entry((i, pos)).or_default().push((key, found.clone()));
+        found
+    }
+
+    fn step(&mut self, part: &Part, i: usize, pos: usize, acc: &Vars) -> Found {
+        let s = self.s;
+        let e = match part {
+            Part::Literal(lit) => return self.run(i + 1, pos + lit.len(), acc),
+            Part::Expression(e) => e,
+        };
+        let allowed = expression_allowed(e.operator);
+        let max = pos + s[pos..].bytes().take(expression_limit(e)).take_while(|c| allowed.contains(*c)).count();
+        let mut found = Vec::new();
+        for end in pos..=max {
+            if found.len() >= MAX_FOUND {
+                break;
+            }
+            if !self.reach[i + 1][end] {
+                continue;
+            }
+            match bind(e, &s[pos..end]) {
+                Bind::Fail => {}
+                Bind::Ambiguous => {
+                    if !self.run(i + 1, end, acc).is_empty() {
+                        push_distinct(&mut found, None);
+                    }
+                }
+                Bind::Vars(vars) => {
+                    // 同名变量出现多次时取值必须一致
+                    if vars.iter().any(|(k, v)| acc.get(k).is_some_and(|old| old != v)) {
+                        continue;
+                    }
+                    let added: Vars = vars.into_iter().filter(|(k, _)| !acc.contains_key(k)).collect();
+                    let mut next = acc.clone();
+                    next.extend(added.clone());
+                    for m in self.run(i + 1, end, &next) {
+                        push_distinct(
+                            &mut found,
+                            m.map(|mut m| {
+                                m.extend(added.clone());
+                                m
+                            }),
+                        );
+                    }
+                }
+            }
+        }
+        found
+    }
+}
+
+fn push_distinct(found: &mut Found, m: Option<Vars>) {
+    if found.len() < MAX_FOUND && !found.contains(&m) {
+        found.push(m);
+    }
+}
+
+// 从后向前计算, 表达式可以匹配一段允许的字符, 字面量必须原样出现
+fn reachable(parts: &[Part], s: &str) -> Vec<Vec<bool>> {
+    let bs = s.as_bytes();
+    let n = bs.len();
+    let mut reach = alloc::vec![alloc::vec![false; n + 1]; parts.len() + 1];
+    reach[parts.len()][n] = true;
+    for i in (0..parts.len()).rev() {
+        match &parts[i] {
+            Part::Literal(lit) => {
+                for pos in 0..=n {
+                    let end = pos + lit.len();
+                    reach[i][pos] = end <= n && bs[pos..].starts_with(lit.as_bytes()) && reach[i + 1][end];
+                }
+            }
+            Part::Expression(e) => {
+                let allowed = expression_allowed(e.operator);
+                let limit = expression_limit(e);
+                // next 为不小于 pos 的最近可达位置, run_end 为允许字符的结尾
+                let (mut next, mut run_end) = (None, n);
+                for pos in (0..=n).rev() {
+                    if reach[i + 1][pos] {
+                        next = Some(pos);
+                    }
+                    if pos < n && !allowed.contains(bs[pos]) {
+                        run_end = pos;
+                    }
+                    reach[i][pos] = next.is_some_and(|end| end <= run_end && end - pos <= limit);
+                }
+            }
+        }
+    }
+    reach
+}
+
+// 全部变量都有前缀修饰时, 展开结果的长度有上限
+fn expression_limit(e: &Expression) -> usize {
+    let mut limit = e.operator.first().len();
+    for spec in &e.vars {
+        match spec.modifier {
+            // 每个字符编码后最多 12 字节
+            Modifier::Prefix(n) => limit += spec.name.len() + e.operator.sep().len() + 1 + n as usize * 12,
+            _ => return MAX_EXPRESSION_LEN,
+        }
+    }
+    limit.min(MAX_EXPRESSION_LEN)
+}
+
+fn later_names(parts: &[Part]) -> Vec<BTreeSet<String>> {
+    let mut later = alloc::vec![BTreeSet::new(); parts.len() + 1];
+    for (i, part) in parts.iter().enumerate().rev() {
+        let mut names = later[i + 1].clone();
+        if let Part::Expression(e) = part {
+            names.extend(e.vars.iter().map(|v| v.name.clone()));
+        }
+        later[i] = names;
+    }
+    later
+}
+
+// 表达式展开后可能出现的字符
+fn expression_allowed(op: Operator) -> Allowed {
+    let base = if op.allow_reserved() { reserved_allowed() } else { UNRESERVED };
+    base.with(b"%,=").with(op.first().as_bytes()).with(op.sep().as_bytes())
+}
+
+// 编码后的值只能包含运算符允许的字符
+fn valid_value(s: &str, op: Operator) -> bool {
+    let allowed = if op.allow_reserved() { reserved_allowed() } else { UNRESERVED };
+    let bs = s.as_bytes();
+    let mut i = 0;
+    while i < bs.len() {
+        if bs[i] == b'%' {
+            if !bs.get(i + 1..i + 3).is_some_and(|h| h.iter().all(|c| c.is_ascii_hexdigit())) {
+                return false;
+            }
+            i += 3;
+            continue;
+        }
+        if !allowed.contains(bs[i]) {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+fn bind(e: &Expression, s: &str) -> Bind {
+    // 所有变量都未定义
+    if s.is_empty() {
+        return Bind::Vars(Vec::new());
+    }
+    let rest = match s.strip_prefix(e.operator.first()) {
+        Some(rest) => rest,
+        None => return Bind::Fail,
+    };
+    if e.operator.named() {
+        bind_named(e, rest)
+    } else {
+        bind_unnamed(e, rest)
+    }
+}
+
+// ';', '?', '&' 运算符, 按名称对应, 与顺序无关
+fn bind_named(e: &Expression, rest: &str) -> Bind {
+    let op = e.operator;
+    let explode: Vec<&VarSpec> = e.vars.iter().filter(|v| v.modifier == Modifier::Explode).collect();
+    let mut vars: Vec<(String, Value)> = Vec::new();
+    for piece in rest.split(op.sep()) {
+        let (name, val) = piece.split_once('=').unwrap_or((piece, ""));
+        if name.is_empty() || !valid_value(name, op) || !val.split(',').all(|v| valid_value(v, op)) {
+            return Bind::Fail;
+        }
+        let entry = vars.iter().position(|(k, _)| k == name);
+        match e.vars.iter().find(|v| v.name == name) {
+            Some(spec) if spec.modifier == Modifier::Explode => match entry {
+                Some(i) => match &mut vars[i].1 {
+                    Value::List(l) => l.push(percent_decode(val)),
+                    _ => return Bind::Fail,
+                },
+                None => vars.push((name.to_string(), Value::List([percent_decode(val)].into()))),
+            },
+            Some(spec) => {
+                let value = string_or_list(val, op);
+                if entry.is_some() || !within_prefix(spec, &value) {
+                    return Bind::Fail;
+                }
+                vars.push((name.to_string(), value));
+            }
+            // 未知的名称只能来自展开的 map
+            None => {
+                let spec = match explode.as_slice() {
+                    [spec] => spec,
+                    _ => return Bind::Fail,
+                };
+                let (k, v) = (percent_decode(name), percent_decode(val));
+                match vars.iter_mut().find(|(n, _)| *n == spec.name) {
+                    Some((_, Value::Map(m))) => m.push((k, v)),
+                    Some(_) => return Bind::Fail,
+                    None => vars.push((spec.name.clone(), Value::Map([(k, v)].into()))),
+                }
+            }
+        }
+    }
+    Bind::Vars(vars)
+}
+
+// 其余运算符按位置对应
+fn bind_unnamed(e: &Expression, rest: &str) -> Bind {
+    let op = e.operator;
+    let pieces: Vec<&str> = rest.split(op.sep()).collect();
+    let specs = &e.vars;
+    if pieces.len() < specs.len() {
+        return if specs.len() == 1 { Bind::Fail } else { Bind::Ambiguous };
+    }
+    // 多出的值由唯一一个可以取多个值的变量吸收
+    let extra = pieces.len() - specs.len();
+    let absorb = if extra == 0 {
+        None
+    } else {
+        let candidates: Vec<usize> = specs
+            .iter()
+            .enumerate()
+            .filter(|(_, v)| v.modifier == Modifier::Explode || (op.sep() == "," && v.modifier == Modifier::None))
+            .map(|(i, _)| i)
+            .collect();
+        match candidates.as_slice() {
+            [i] => Some(*i),
+            [] => return Bind::Fail,
+            _ => return Bind::Ambiguous,
+        }
+    };
+    let mut vars = Vec::new();
+    let mut i = 0;
+    for (j, spec) in specs.iter().enumerate() {
+        let n = if absorb == Some(j) { extra + 1 } else { 1 };
+        let group = &pieces[i..i + n];
+        i += n;
+        let value = match unnamed_value(spec, group, op) {
+            Some(v) => v,
+            None => return Bind::Fail,
+        };
+        vars.push((spec.name.clone(), value));
+    }
+    Bind::Vars(vars)
+}
+
+fn unnamed_value(spec: &VarSpec, group: &[&str], op: Operator) -> Option<Value> {
+    if spec.modifier == Modifier::Explode {
+        // 非保留模式下 '=' 会被编码, 出现即为 map
+        if !op.allow_reserved() && group.iter().all(|p| p.contains('=')) {
+            let mut m = Vec::new();
+            for p in group {
+                let (k, v) = p.split_once('=')?;
+                if !valid_value(k, op) || !valid_value(v, op) {
+                    return None;
+                }
+                m.push((percent_decode(k), percent_decode(v)));
+            }
+            return Some(Value::Map(m));
+        }
+        if !group.iter().all(|p| valid_value(p, op)) {
+            return None;
+        }
+        return Some(Value::List(group.iter().map(|p| percent_decode(p)).collect()));
+    }
+    if group.len() > 1 {
+        if !group.iter().all(|p| valid_value(p, op)) {
+            return None;
+        }
+        return Some(Value::List(group.iter().map(|p| percent_decode(p)).collect()));
+    }
+    let val = group[0];
+    if !op.allow_reserved() && val.split(',').any(|v| !valid_value(v, op)) {
+        return None;
+    }
+    if op.allow_reserved() && !valid_value(val, op) {
+        return None;
+    }
+    Some(string_or_list(val, op)).filter(|v| within_prefix(spec, v))
+}
+
+// 前缀修饰只截取字符串, 超过长度的值不可能由展开得到
+fn within_prefix(spec: &VarSpec, value: &Value) -> bool {
+    match (spec.modifier, value) {
+        (Modifier::Prefix(n), Value::String(s)) => s.chars().count() <= n as usize,
+        _ => true,
+    }
+}
+
+// 非保留模式下字符串中的 ',' 会被编码, 出现即为 list
+fn string_or_list(val: &str, op: Operator) -> Value {
+    if !op.allow_reserved() && val.contains(',') {
+        return Value::List(val.split(',').map(percent_decode).collect());
+    }
+    Value::String(percent_decode(val))
+}
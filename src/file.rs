@@ -0,0 +1,189 @@
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use super::authority::Host;
+use super::encoder::{percent_decode_bytes, percent_encode, percent_encode_bytes, Allowed, PCHAR};
+use super::error::{Error, Result};
+use super::uri::URI;
+use super::Parser;
+
+// 路径中不编码的字符
+const PATH_CHARS: Allowed = PCHAR.with(b"/");
+
+// 文件路径的书写方式, 与运行平台无关
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PathStyle {
+    // "/home/a"
+    Unix,
+    // "C:\a", "\\server\share\a"
+    Windows,
+}
+
+impl PathStyle {
+    pub fn native() -> Self {
+        if cfg!(windows) {
+            PathStyle::Windows
+        } else {
+            PathStyle::Unix
+        }
+    }
+}
+
+// rfc8089
+impl URI {
+    // 路径必须是绝对路径, unix 下按原始字节编码, 可以不是 utf-8
+    #[cfg(feature = "std")]
+    pub fn from_file_path(path: &std::path::Path) -> Result<URI> {
+        #[cfg(unix)]
+        {
+            use std::os::unix::ffi::OsStrExt;
+            from_unix_bytes(path.as_os_str().as_bytes())
+        }
+        #[cfg(not(unix))]
+        {
+            match path.to_str() {
+                Some(s) => URI::from_path_str(s, PathStyle::native()),
+                None => Err(Error::Path(format!("path is not valid unicode, \'{}\'", path.display()))),
+            }
+        }
+    }
+
+    // host 只能为空或 localhost, windows 下其他 host 转为 UNC 路径
+    #[cfg(feature = "std")]
+    pub fn to_file_path(&self) -> Result<std::path::PathBuf> {
+        #[cfg(unix)]
+        {
+            use std::os::unix::ffi::OsStringExt;
+            let bytes = self.unix_path_bytes()?;
+            Ok(std::path::PathBuf::from(std::ffi::OsString::from_vec(bytes)))
+        }
+        #[cfg(not(unix))]
+        {
+            Ok(std::path::PathBuf::from(self.to_path_string(PathStyle::native())?))
+        }
+    }
+
+    // 按文本处理, 可以在任意平台上转换另一种风格的路径
+    pub fn from_path_str(s: &str, style: PathStyle) -> Result<URI> {
+        match style {
+            PathStyle::Unix => from_unix_bytes(s.as_bytes()),
+            PathStyle::Windows => from_windows_str(s),
+        }
+    }
+
+    // 解码后不是合法 utf-8 时报错
+    pub fn to_path_string(&self, style: PathStyle) -> Result<String> {
+        match style {
+            PathStyle::Unix => String::from_utf8(self.unix_path_bytes()?).map_err(|_| self.path_error("path is not valid utf-8")),
+            PathStyle::Windows => self.windows_path(),
+        }
+    }
+
+    // 忽略 query 与 fragment, 返回 host 与解码后的各段
+    fn file_parts(&self) -> Result<(Option<String>, Vec<Vec<u8>>)> {
+        if !self.scheme().is_some_and(|s| s.as_str().eq_ignore_ascii_case("file")) {
+            return Err(self.path_error("not a file uri"));
+        }
+        let host = match self.host().map(|h| h.as_str()) {
+            None | Some("") => None,
+            Some(h) if h.eq_ignore_ascii_case("localhost") => None,
+            Some(h) => Some(h.to_string()),
+        };
+        let path = self.path().map(|p| p.as_str()).unwrap_or("");
+        let rest = match path.strip_prefix('/') {
+            Some(rest) => rest,
+            None => return Err(self.path_error("file uri path must be absolute")),
+        };
+        let mut segments = Vec::new();
+        for seg in rest.split('/') {
+            let seg = percent_decode_bytes(seg);
+            // 解码出的分隔符会改变路径结构
+            if seg.iter().any(|c| matches!(c, b'/' | 0)) {
+                return Err(self.path_error("encoded '/' or NUL in file uri path"));
+            }
+            segments.push(seg);
+        }
+        Ok((host, segments))
+    }
+
+    fn unix_path_bytes(&self) -> Result<Vec<u8>> {
+        let (host, segments) = self.file_parts()?;
+        if host.is_some() {
+            return Err(self.path_error("file uri with a remote host has no unix path"));
+        }
+        let mut buf = Vec::new();
+        for seg in segments {
+            buf.push(b'/');
+            buf.extend(seg);
+        }
+        Ok(buf)
+    }
+
+    fn windows_path(&self) -> Result<String> {
+        let (host, segments) = self.file_parts()?;
+        let mut segs = Vec::with_capacity(segments.len());
+        for seg in segments {
+            match String::from_utf8(seg) {
+                Ok(s) if !s.contains('\\') => segs.push(s),
+                _ => return Err(self.path_error("invalid segment in windows path")),
+            }
+        }
+        if let Some(host) = host {
+            if segs.first().is_none_or(|s| s.is_empty()) {
+                return Err(self.path_error("UNC path requires a share name"));
+            }
+            return Ok(format!("\\\\{}\\{}", host, segs.join("\\")));
+        }
+        // "C:" 或旧式的 "C|"
+        match segs.first().map(|s| s.as_bytes()) {
+            Some([d, b':' | b'|']) if d.is_ascii_alphabetic() => {
+                segs[0] = format!("{}:", *d as char);
+                if segs.len() == 1 {
+                    segs.push(String::new());
+                }
+                Ok(segs.join("\\"))
+            }
+            _ => Err(self.path_error("windows path requires a drive letter or a host")),
+        }
+    }
+
+    fn path_error(&self, reason: &str) -> Error {
+        Error::Path(format!("{}, \'{}\'", reason, self))
+    }
+}
+
+fn from_unix_bytes(bs: &[u8]) -> Result<URI> {
+    if bs.first() != Some(&b'/') {
+        return Err(Error::Path(format!("path must be absolute, \'{}\'", String::from_utf8_lossy(bs))));
+    }
+    URI::decode(&format!("file://{}", percent_encode_bytes(bs, &PATH_CHARS)))
+}
+
+fn from_windows_str(s: &str) -> Result<URI> {
+    let invalid = || Error::Path(format!("path must be absolute, \'{}\'", s));
+    // 去掉 "\\?\" 前缀
+    let path = s.strip_prefix(r"\\?\").map_or(s.to_string(), |p| match p.strip_prefix(r"UNC\") {
+        Some(unc) => format!(r"\\{}", unc),
+        None => p.to_string(),
+    });
+    let path = path.replace('/', "\\");
+    if let Some(unc) = path.strip_prefix(r"\\") {
+        let (host, rest) = unc.split_once('\\').ok_or_else(invalid)?;
+        if host.is_empty() || rest.is_empty() {
+            return Err(invalid());
+        }
+        // host 原样写入 uri, 不能含 ':', '#' 等改变结构的字符, 也不接受百分比编码
+        if Host::validate(host).is_err() || host.contains('%') {
+            return Err(Error::Path(format!("invalid UNC host \'{}\' in \'{}\'", host, s)));
+        }
+        let rest = percent_encode(&rest.replace('\\', "/"), &PATH_CHARS);
+        return URI::decode(&format!("file://{}/{}", host, rest));
+    }
+    match path.as_bytes() {
+        [d, b':', b'\\', ..] if d.is_ascii_alphabetic() => {
+            let rest = percent_encode(&path[3..].replace('\\', "/"), &PATH_CHARS);
+            URI::decode(&format!("file:///{}:/{}", *d as char, rest))
+        }
+        _ => Err(invalid()),
+    }
+}
@@ -1,24 +1,51 @@
-use super::encoder::{Decoder, Encoder};
+use alloc::vec;
+use alloc::string::{String, ToString};
+use super::encoder::{normalize, percent_decode, percent_encode, Allowed, Decoder, Encoder};
 use super::Parser;
 use super::error::Result;
 
-#[derive(Debug)]
-pub struct Fragment(String);
+#[derive(Debug, Clone)]
+pub struct Fragment {
+    decoded: String,
+    serialized: String,
+}
+
+impl Fragment {
+    // 参数为未编码的原文
+    pub fn new(s: &str) -> Self {
+        Fragment {
+            decoded: s.to_string(),
+            serialized: percent_encode(s, &Fragment::allowed()),
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        self.serialized.as_str()
+    }
+
+    pub fn decoded(&self) -> &str {
+        self.decoded.as_str()
+    }
+
+    fn allowed() -> Allowed {
+        let mut allowed = Allowed::new();
+        allowed.set_unreserved().set_subdelims().set(vec![b':', b'@', b'/', b'?']);
+        allowed
+    }
+}
 
 impl Parser for Fragment {
     fn decode(s: &str) -> crate::Result<Self> {
-        let mut dec = Decoder::new(s);
-        dec.set_decode_pct();
-        dec.allowed().set_unreserved().set_subdelims().set(vec![b'/', b'?']);
-        let r = dec.decode()?;
-        Ok(Fragment(r))
+        let serialized = normalize(s, &Fragment::allowed())?;
+        Ok(Fragment {
+            decoded: percent_decode(serialized.as_str()),
+            serialized,
+        })
     }
 
     fn encode(&self) -> crate::Result<String> {
-        let mut enc = Encoder::new(self.0.as_str());
-        enc.set_encode_pct();
-        enc.allowed().set_unreserved().set_subdelims().set(vec![b'/', b'?']);
-        let r = enc.encode()?;
-        Ok(r)
+        Ok(self.serialized.clone())
     }
-}
\ No newline at end of file
+}
+
+impl_str_conv!(Fragment);
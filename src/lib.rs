@@ -1,17 +1,107 @@
-#![feature(str_internals)]
+#![cfg_attr(not(feature = "std"), no_std)]
 #![allow(unused)]
 
+extern crate alloc;
+#[cfg(all(test, not(feature = "std")))]
+#[macro_use]
+extern crate std;
+
+// 为保存了规范化编码结果的类型实现字符串相关的转换, 类型需提供 as_str
+macro_rules! impl_str_conv {
+    ($t:ty) => {
+        impl $t {
+            // 直接写出已缓存的编码结果, 不产生中间分配
+            pub fn write_to(&self, w: &mut impl core::fmt::Write) -> core::fmt::Result {
+                w.write_str(self.as_str())
+            }
+
+            #[cfg(feature = "std")]
+            pub fn write_to_io(&self, w: &mut impl std::io::Write) -> std::io::Result<()> {
+                w.write_all(self.as_str().as_bytes())
+            }
+
+            // 编码后的字节长度
+            pub fn encoded_len(&self) -> usize {
+                self.as_str().len()
+            }
+        }
+
+        impl core::fmt::Display for $t {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                f.write_str(self.as_str())
+            }
+        }
+
+        impl core::str::FromStr for $t {
+            type Err = crate::error::Error;
+
+            fn from_str(s: &str) -> crate::Result<Self> {
+                <$t as crate::Parser>::decode(s)
+            }
+        }
+
+        impl TryFrom<&str> for $t {
+            type Error = crate::error::Error;
+
+            fn try_from(s: &str) -> crate::Result<Self> {
+                <$t as crate::Parser>::decode(s)
+            }
+        }
+
+        impl TryFrom<alloc::string::String> for $t {
+            type Error = crate::error::Error;
+
+            fn try_from(s: alloc::string::String) -> crate::Result<Self> {
+                <$t as crate::Parser>::decode(s.as_str())
+            }
+        }
+
+        impl AsRef<str> for $t {
+            fn as_ref(&self) -> &str {
+                self.as_str()
+            }
+        }
+
+        impl From<$t> for alloc::string::String {
+            fn from(value: $t) -> alloc::string::String {
+                alloc::string::ToString::to_string(value.as_str())
+            }
+        }
+    };
+}
+
 pub mod encoder;
 pub mod authority;
+pub mod builder;
+pub mod csp;
+pub mod data;
 pub mod error;
+pub mod extract;
+pub mod file;
 pub mod fragment;
+pub mod mailto;
+pub mod origin;
 pub mod rpart;
+pub mod router;
 pub mod path;
+pub mod pattern;
 pub mod query;
+pub mod reference;
+pub mod resolve;
 pub mod scheme;
+pub mod stream;
+pub mod target;
+pub mod template;
+#[cfg(feature = "serde")]
+pub mod serde;
 pub mod uri;
+pub mod validate;
 
 pub use error::Result;
+use alloc::string::String;
+
+// 日志输出时替换敏感信息所用的掩码
+pub const REDACTED: &str = "****";
 
 pub trait Parser: Sized {
     fn decode(s: &str) -> Result<Self>;
@@ -0,0 +1,212 @@
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use super::encoder::{percent_decode, percent_encode, Allowed, UNRESERVED};
+use super::error::{Error, Result};
+use super::uri::URI;
+use super::Parser;
+
+// rfc6068, mailto:[to *("," to)]["?" hfield *("&" hfield)]
+// 查询中的 to 字段解析时并入收件人列表
+#[derive(Debug, Clone, Default)]
+pub struct MailtoUri {
+    to: Vec<String>,
+    // 解码后的字段名与值, 字段名不区分大小写
+    headers: Vec<(String, String)>,
+    serialized: String,
+}
+
+// 地址中的 ',' 为分隔符, 需要编码
+const ADDR_CHARS: Allowed = UNRESERVED.with(b"!$'()*+;:@");
+// rfc6068 的 qchar, 不含 '/', '?', '=', '&'
+const QCHARS: Allowed = UNRESERVED.with(b"!$'()*+,;:@");
+
+impl MailtoUri {
+    pub fn new() -> Self {
+        let mut m = MailtoUri::default();
+        m.serialize();
+        m
+    }
+
+    pub fn as_str(&self) -> &str {
+        self.serialized.as_str()
+    }
+
+    pub fn to(&self) -> &[String] {
+        self.to.as_slice()
+    }
+
+    pub fn cc(&self) -> Vec<&str> {
+        split_addresses(self.header("cc"))
+    }
+
+    pub fn bcc(&self) -> Vec<&str> {
+        split_addresses(self.header("bcc"))
+    }
+
+    pub fn subject(&self) -> Option<&str> {
+        self.header("subject")
+    }
+
+    // 换行为 "\r\n"
+    pub fn body(&self) -> Option<&str> {
+        self.header("body")
+    }
+
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.iter().find(|(k, _)| k.eq_ignore_ascii_case(name)).map(|(_, v)| v.as_str())
+    }
+
+    // 不含 to 字段
+    pub fn headers(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.headers.iter().map(|(k, v)| (k.as_str(), v.as_str()))
+    }
+
+    pub fn add_to(&mut self, addr: &str) -> Result<&mut Self> {
+        check_address(addr)?;
+        self.to.push(addr.to_string());
+        self.serialize();
+        Ok(self)
+    }
+
+    pub fn clear_to(&mut self) -> &mut Self {
+        self.to.clear();
+        self.serialize();
+        self
+    }
+
+    pub fn add_cc(&mut self, addr: &str) -> Result<&mut Self> {
+        self.append_address("cc", addr)
+    }
+
+    pub fn add_bcc(&mut self, addr: &str) -> Result<&mut Self> {
+        self.append_address("bcc", addr)
+    }
+
+    pub fn set_subject(&mut self, subject: &str) -> Result<&mut Self> {
+        self.set_header("subject", subject)
+    }
+
+    // 单独的 '\r' 或 '\n' 统一为 "\r\n", rfc6068 5
+    pub fn set_body(&mut self, body: &str) -> Result<&mut Self> {
+        let body = body.replace("\r\n", "\n").replace('\r', "\n").replace('\n', "\r\n");
+        self.set_header("body", &body)
+    }
+
+    // 已存在时替换, 收件人应使用 add_to
+    pub fn set_header(&mut self, name: &str, value: &str) -> Result<&mut Self> {
+        check_header(name, value)?;
+        match self.headers.iter_mut().find(|(k, _)| k.eq_ignore_ascii_case(name)) {
+            Some((_, v)) => *v = value.to_string(),
+            None => self.headers.push((name.to_string(), value.to_string())),
+        }
+        self.serialize();
+        Ok(self)
+    }
+
+    pub fn remove_header(&mut self, name: &str) -> &mut Self {
+        self.headers.retain(|(k, _)| !k.eq_ignore_ascii_case(name));
+        self.serialize();
+        self
+    }
+
+    pub fn to_uri(&self) -> Result<URI> {
+        URI::decode(self.as_str())
+    }
+
+    fn append_address(&mut self, name: &str, addr: &str) -> Result<&mut Self> {
+        check_address(addr)?;
+        let value = match self.header(name) {
+            Some(v) if !v.is_empty() => format!("{},{}", v, addr),
+            _ => addr.to_string(),
+        };
+        self.set_header(name, &value)
+    }
+
+    fn serialize(&mut self) {
+        let to: Vec<String> = self.to.iter().map(|a| percent_encode(a, &ADDR_CHARS)).collect();
+        let fields: Vec<String> = self
+            .headers
+            .iter()
+            .map(|(k, v)| format!("{}={}", percent_encode(k, &QCHARS), percent_encode(v, &QCHARS)))
+            .collect();
+        self.serialized = if fields.is_empty() {
+            format!("mailto:{}", to.join(","))
+        } else {
+            format!("mailto:{}?{}", to.join(","), fields.join("&"))
+        };
+    }
+}
+
+impl TryFrom<&URI> for MailtoUri {
+    type Error = Error;
+
+    fn try_from(u: &URI) -> Result<Self> {
+        if !u.scheme().is_some_and(|s| s.as_str().eq_ignore_ascii_case("mailto")) || u.authority().is_some() {
+            return Err(Error::Decode(format!("not a mailto uri, \'{}\'", u)));
+        }
+        let mut m = MailtoUri::default();
+        for addr in u.path().map(|p| p.as_str()).unwrap_or("").split(',').filter(|a| !a.is_empty()) {
+            let addr = percent_decode(addr);
+            check_address(&addr)?;
+            m.to.push(addr);
+        }
+        for (k, v) in u.query().map(|q| q.pairs()).unwrap_or(&[]) {
+            let v = v.clone().unwrap_or_default();
+            if k.eq_ignore_ascii_case("to") {
+                for addr in split_addresses(Some(&v)) {
+                    check_address(addr)?;
+                    m.to.push(addr.to_string());
+                }
+            } else {
+                check_header(k, &v)?;
+                // 重复的 cc, bcc 合并为一个地址列表
+                let repeated = m.headers.iter_mut().find(|(n, _)| n.eq_ignore_ascii_case(k));
+                match repeated {
+                    Some((n, old)) if n.eq_ignore_ascii_case("cc") || n.eq_ignore_ascii_case("bcc") => {
+                        old.push(',');
+                        old.push_str(&v);
+                    }
+                    _ => m.headers.push((k.clone(), v)),
+                }
+            }
+        }
+        m.serialize();
+        Ok(m)
+    }
+}
+
+impl Parser for MailtoUri {
+    fn decode(s: &str) -> Result<Self> {
+        MailtoUri::try_from(&URI::decode(s)?)
+    }
+
+    fn encode(&self) -> Result<String> {
+        Ok(self.serialized.clone())
+    }
+}
+
+impl_str_conv!(MailtoUri);
+
+// rfc6068 5, 只有 body 中可以出现换行
+fn check_header(name: &str, value: &str) -> Result<()> {
+    if name.is_empty() || name.eq_ignore_ascii_case("to") || !name.bytes().all(|c| c.is_ascii_graphic() && c != b':') {
+        return Err(Error::Invalid(format!("invalid mailto header name \'{}\'", name)));
+    }
+    if !name.eq_ignore_ascii_case("body") && value.contains(['\r', '\n']) {
+        return Err(Error::Invalid(format!("line break in mailto header \'{}\'", name)));
+    }
+    Ok(())
+}
+
+fn split_addresses(s: Option<&str>) -> Vec<&str> {
+    s.unwrap_or("").split(',').map(|a| a.trim()).filter(|a| !a.is_empty()).collect()
+}
+
+// 只做最基本的 addr-spec 检查
+fn check_address(addr: &str) -> Result<()> {
+    match addr.rsplit_once('@') {
+        Some((local, domain)) if !local.is_empty() && !domain.is_empty() && !addr.contains([',', '\r', '\n']) => Ok(()),
+        _ => Err(Error::Invalid(format!("invalid mail address \'{}\'", addr))),
+    }
+}
@@ -0,0 +1,211 @@
+use alloc::format;
+use alloc::string::{String, ToString};
+use core::fmt::{Display, Formatter};
+use super::scheme::default_port;
+use super::uri::URI;
+use super::Parser;
+
+// 查询 host 的公共后缀, 如 "a.example.co.uk" -> "co.uk"
+// 内置列表只是公共后缀列表的子集, 需要完整结果时由调用方提供实现
+pub trait PublicSuffixList {
+    // 未知时返回 None, 此时 host 没有可注册域名
+    fn public_suffix<'a>(&self, host: &'a str) -> Option<&'a str>;
+}
+
+// 内置的常用公共后缀, 不在其中的后缀视为未知, 如 "co.il"
+// 已知后缀下未收录的私有后缀(如 "*.s3.amazonaws.com" 之外的云服务域名)仍会被当作可注册域名
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BuiltinSuffixes;
+
+const PUBLIC_SUFFIXES: &[&str] = &[
+    "com", "net", "org", "edu", "gov", "mil", "int", "info", "biz", "io", "dev", "app",
+    "de", "fr", "nl", "ch", "se", "eu", "us", "ca",
+    "co.uk", "org.uk", "ac.uk", "gov.uk", "ltd.uk", "plc.uk", "me.uk",
+    "com.au", "net.au", "org.au", "edu.au", "gov.au",
+    "co.jp", "ne.jp", "or.jp", "ac.jp", "go.jp",
+    "com.cn", "net.cn", "org.cn", "gov.cn", "edu.cn",
+    "com.hk", "com.tw", "co.kr", "co.nz", "co.in", "com.br", "com.mx", "co.za",
+    "github.io", "gitlab.io", "herokuapp.com", "appspot.com", "blogspot.com", "s3.amazonaws.com",
+    "cloudfront.net", "azurewebsites.net", "pages.dev", "workers.dev", "vercel.app", "netlify.app",
+];
+
+impl PublicSuffixList for BuiltinSuffixes {
+    // 取最长的匹配项
+    fn public_suffix<'a>(&self, host: &'a str) -> Option<&'a str> {
+        PUBLIC_SUFFIXES
+            .iter()
+            .filter(|s| host == **s || (host.ends_with(*s) && host.as_bytes()[host.len() - s.len() - 1] == b'.'))
+            .map(|s| s.len())
+            .max()
+            .map(|n| &host[host.len() - n..])
+    }
+}
+
+// rfc6454 4, 未知 scheme 或没有 host 时为 opaque origin
+#[derive(Debug, Clone)]
+pub enum Origin {
+    Tuple {
+        scheme: String,
+        host: String,
+        port: u16,
+    },
+    // 每个 opaque origin 都是唯一的, 与任何 origin 都不相同
+    Opaque,
+}
+
+impl Origin {
+    pub fn is_opaque(&self) -> bool {
+        matches!(self, Origin::Opaque)
+    }
+
+    pub fn scheme(&self) -> Option<&str> {
+        match self {
+            Origin::Tuple { scheme, .. } => Some(scheme.as_str()),
+            Origin::Opaque => None,
+        }
+    }
+
+    pub fn host(&self) -> Option<&str> {
+        match self {
+            Origin::Tuple { host, .. } => Some(host.as_str()),
+            Origin::Opaque => None,
+        }
+    }
+
+    // 有效端口, 没有显式端口时为 scheme 的默认端口
+    pub fn port(&self) -> Option<u16> {
+        match self {
+            Origin::Tuple { port, .. } => Some(*port),
+            Origin::Opaque => None,
+        }
+    }
+
+    // rfc6454 6.2, 默认端口省略, opaque origin 为 "null"
+    pub fn ascii_serialization(&self) -> String {
+        match self {
+            Origin::Tuple { scheme, host, port } => {
+                if default_port(scheme) == Some(*port) {
+                    format!("{}://{}", scheme, host)
+                } else {
+                    format!("{}://{}:{}", scheme, host, port)
+                }
+            }
+            Origin::Opaque => "null".to_string(),
+        }
+    }
+
+    pub fn is_same_origin(&self, other: &Origin) -> bool {
+        match (self, other) {
+            (Origin::Tuple { scheme: s1, host: h1, port: p1 }, Origin::Tuple { scheme: s2, host: h2, port: p2 }) => {
+                s1 == s2 && h1 == h2 && p1 == p2
+            }
+            _ => false,
+        }
+    }
+
+    // html 中的 schemeful same-site, 不比较端口, 使用内置的公共后缀
+    pub fn is_same_site(&self, other: &Origin) -> bool {
+        self.is_same_site_with(other, &BuiltinSuffixes)
+    }
+
+    pub fn is_same_site_with(&self, other: &Origin, psl: &impl PublicSuffixList) -> bool {
+        match (self, other) {
+            (Origin::Tuple { scheme: s1, host: h1, .. }, Origin::Tuple { scheme: s2, host: h2, .. }) => {
+                if s1 != s2 {
+                    return false;
+                }
+                if h1 == h2 {
+                    return true;
+                }
+                match (registrable_domain(h1, psl), registrable_domain(h2, psl)) {
+                    (Some(d1), Some(d2)) => d1 == d2,
+                    _ => false,
+                }
+            }
+            _ => false,
+        }
+    }
+
+    // 公共后缀加一个标签, ip 地址, 公共后缀本身和未知后缀没有可注册域名
+    pub fn registrable_domain(&self) -> Option<&str> {
+        self.registrable_domain_with(&BuiltinSuffixes)
+    }
+
+    pub fn registrable_domain_with(&self, psl: &impl PublicSuffixList) -> Option<&str> {
+        registrable_domain(self.host()?, psl)
+    }
+}
+
+impl Display for Origin {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.ascii_serialization().as_str())
+    }
+}
+
+impl URI {
+    pub fn origin(&self) -> Origin {
+        let scheme = match self.scheme() {
+            Some(s) => s.as_str().to_ascii_lowercase(),
+            None => return Origin::Opaque,
+        };
+        // blob: 使用其中 URL 的 origin
+        if scheme == "blob" {
+            return match self.path().map(|p| URI::decode(p.as_str())) {
+                Some(Ok(inner)) if matches!(inner.scheme().map(|s| s.as_str()), Some("http" | "https")) => inner.origin(),
+                _ => Origin::Opaque,
+            };
+        }
+        let (host, default) = match (self.host(), default_port(&scheme)) {
+            (Some(h), Some(d)) if !h.is_empty() => (h, d),
+            _ => return Origin::Opaque,
+        };
+        // 末尾带 '.' 的 host 是不同的 origin, 保持原样
+        let host = lowercase_host(host.as_str());
+        Origin::Tuple {
+            scheme,
+            host,
+            port: self.port().map(|p| p.as_u16()).unwrap_or(default),
+        }
+    }
+
+    pub fn is_same_origin(&self, other: &URI) -> bool {
+        self.origin().is_same_origin(&other.origin())
+    }
+
+    pub fn is_same_site(&self, other: &URI) -> bool {
+        self.origin().is_same_site(&other.origin())
+    }
+}
+
+fn registrable_domain<'a>(host: &'a str, psl: &impl PublicSuffixList) -> Option<&'a str> {
+    // ip-literal 或 ipv4
+    if host.starts_with('[') || host.parse::<core::net::Ipv4Addr>().is_ok() {
+        return None;
+    }
+    let suffix = psl.public_suffix(host)?.len();
+    if suffix >= host.len() {
+        return None;
+    }
+    // 公共后缀前的一个标签
+    let head = &host[..host.len() - suffix - 1];
+    let start = head.rfind('.').map_or(0, |i| i + 1);
+    Some(&host[start..])
+}
+
+// 百分比编码中的十六进制保持 uri 规范化后的大写
+fn lowercase_host(s: &str) -> String {
+    let mut buf = String::with_capacity(s.len());
+    let mut pct = 0;
+    for c in s.chars() {
+        if c == '%' {
+            pct = 2;
+            buf.push(c);
+        } else if pct > 0 {
+            pct -= 1;
+            buf.push(c);
+        } else {
+            buf.push(c.to_ascii_lowercase());
+        }
+    }
+    buf
+}
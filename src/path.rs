@@ -1,48 +1,189 @@
-use std::fs;
-use crate::encoder::{Decoder, Encoder};
+use alloc::format;
+use alloc::vec;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use crate::encoder::{normalize, percent_decode, percent_encode, Allowed, Decoder, Encoder};
 use crate::Parser;
 use super::error::{Error, Result};
 
-#[derive(Debug)]
-pub struct Path(String);
+#[derive(Debug, Clone)]
+pub struct Path {
+    decoded: String,
+    serialized: String,
+}
 
-// todo: 增加消除点段
 impl Path {
+    // 参数为未编码的原文, '/' 作为分段符保留
     pub fn new(s: &str) -> Self {
-        Path(s.to_string())
+        Path {
+            decoded: s.to_string(),
+            serialized: percent_encode(s, &Path::allowed()),
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        self.serialized.as_str()
+    }
+
+    pub fn decoded(&self) -> &str {
+        self.decoded.as_str()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.serialized.is_empty()
+    }
+
+    pub fn is_absolute(&self) -> bool {
+        self.serialized.starts_with('/')
+    }
+
+    // 先按编码后的 '/' 分段再逐段解码, "%2F" 不作为分段符
+    pub fn segments(&self) -> Vec<String> {
+        self.serialized.trim_start_matches('/').split('/').map(percent_decode).collect()
+    }
+
+    fn allowed() -> Allowed {
+        let mut allowed = Allowed::new();
+        allowed.set_unreserved().set_subdelims().set(vec![b':', b'@', b'/']);
+        allowed
+    }
+
+    // 消除点段, rfc3986 5.2.4
+    pub fn normalized(&self) -> Self {
+        let s = remove_dot_segments(self.serialized.as_str());
+        Path::decode(s.as_str()).unwrap_or_else(|_| self.clone())
+    }
+}
+
+// rfc3986 5.2.4, 输入输出均为编码后的路径
+pub fn remove_dot_segments(s: &str) -> String {
+    let mut input = s;
+    let mut output: Vec<&str> = Vec::new();
+    while !input.is_empty() {
+        if let Some(rest) = input.strip_prefix("../") {
+            input = rest;
+        } else if let Some(rest) = input.strip_prefix("./") {
+            input = rest;
+        } else if input.starts_with("/./") {
+            input = &input[2..];
+        } else if input == "/." {
+            input = "/";
+        } else if input.starts_with("/../") {
+            input = &input[3..];
+            output.pop();
+        } else if input == "/.." {
+            input = "/";
+            output.pop();
+        } else if input == "." || input == ".." {
+            input = "";
+        } else {
+            // 移动第一个路径段(含开头的 '/')到输出
+            let start = if input.starts_with('/') { 1 } else { 0 };
+            let end = input[start..].find('/').map(|i| i + start).unwrap_or(input.len());
+            output.push(&input[..end]);
+            input = &input[end..];
+        }
+    }
+    output.concat()
+}
+
+// 以下类型对应 rfc3986 3.3 中的 path 规则, 供 builder 在类型层面约束组合
+
+// path-abempty, 空或以 '/' 开头, 用于有 authority 的情况
+#[derive(Debug, Clone)]
+pub struct PathAbempty(Path);
+
+impl PathAbempty {
+    pub fn new(s: &str) -> Result<Self> {
+        if !s.is_empty() && !s.starts_with('/') {
+            return Err(Error::Path(format!("path-abempty must be empty or start with '/', \'{}\'", s)));
+        }
+        Ok(PathAbempty(Path::new(s)))
+    }
+}
+
+// path-absolute, 以 '/' 开头但不能以 '//' 开头
+#[derive(Debug, Clone)]
+pub struct PathAbsolute(Path);
+
+impl PathAbsolute {
+    pub fn new(s: &str) -> Result<Self> {
+        if !s.starts_with('/') || s.starts_with("//") {
+            return Err(Error::Path(format!("path-absolute must start with a single '/', \'{}\'", s)));
+        }
+        Ok(PathAbsolute(Path::new(s)))
+    }
+}
+
+// path-rootless, 非空且不以 '/' 开头
+#[derive(Debug, Clone)]
+pub struct PathRootless(Path);
+
+impl PathRootless {
+    pub fn new(s: &str) -> Result<Self> {
+        if s.is_empty() || s.starts_with('/') {
+            return Err(Error::Path(format!("path-rootless must not be empty or start with '/', \'{}\'", s)));
+        }
+        Ok(PathRootless(Path::new(s)))
+    }
+}
+
+// path-noscheme, 在 path-rootless 基础上第一段不能包含 ':'
+#[derive(Debug, Clone)]
+pub struct PathNoScheme(Path);
+
+impl PathNoScheme {
+    pub fn new(s: &str) -> Result<Self> {
+        let p = PathRootless::new(s)?;
+        if s.split('/').next().is_some_and(|seg| seg.contains(':')) {
+            return Err(Error::Path(format!("first segment of path-noscheme cannot contain ':', \'{}\'", s)));
+        }
+        Ok(PathNoScheme(p.0))
+    }
+}
+
+impl From<PathAbempty> for Path {
+    fn from(value: PathAbempty) -> Self {
+        value.0
+    }
+}
+
+impl From<PathAbsolute> for Path {
+    fn from(value: PathAbsolute) -> Self {
+        value.0
+    }
+}
+
+impl From<PathRootless> for Path {
+    fn from(value: PathRootless) -> Self {
+        value.0
+    }
+}
+
+impl From<PathNoScheme> for Path {
+    fn from(value: PathNoScheme) -> Self {
+        value.0
     }
 }
 
 impl Parser for Path {
     fn decode(s: &str) -> Result<Self> {
-        let mut buf: Vec<String> = Vec::new();
-        for sub in s.split('/') {
-            let mut dec = Decoder::new(sub);
-            dec.allow_empty();
-            dec.set_decode_pct();
-            dec.allowed().set_unreserved().set_subdelims().set([b':', b'@'].to_vec());
-            let r = dec.decode()?;
-            buf.push(r)
-        }
-        let r = buf.join("/");
-        Ok(Path(r))
+        // 保留编码过的 '/' 等保留字符, 只规范化编码形式
+        let serialized = normalize(s, &Path::allowed())?;
+        Ok(Path {
+            decoded: percent_decode(serialized.as_str()),
+            serialized,
+        })
     }
 
     fn encode(&self) -> Result<String> {
-        let mut buf: Vec<String> = Vec::new();
-        for sub in self.0.split('/') {
-            let mut enc = Encoder::new(sub);
-            enc.allow_empty();
-            enc.set_encode_pct();
-            enc.allowed().set_unreserved().set_subdelims().set([b':', b'@'].to_vec());
-            let r = enc.encode()?;
-            buf.push(r)
-        }
-        let r = buf.join("/");
-        Ok(r)
+        Ok(self.serialized.clone())
     }
 }
 
+impl_str_conv!(Path);
+
+#[cfg(test)]
 #[test]
 fn parse() {
     let s = "/a/b/";
@@ -51,14 +192,16 @@ fn parse() {
     println!("{:#?}", a)
 }
 
+#[cfg(test)]
 #[test]
 fn decode() {
     let r = Path::decode("a%E4%BB%A3/b/c").unwrap();
     println!("{:?}", r);
 }
 
+#[cfg(test)]
 #[test]
 fn encode() {
-    let s = Path("a代/b/c".to_string());
+    let s = Path::new("a代/b/c");
     println!("{:?}", s.encode())
 }
\ No newline at end of file
@@ -0,0 +1,247 @@
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use super::error::{Error, Result};
+use super::uri::URI;
+use super::Parser;
+
+// 类似浏览器扩展的 match pattern: <scheme>://<host>[:<port>]<path>
+// scheme 为 '*' 时匹配任意 scheme, host 可以为 '*' 或 "*.example.com"
+// 省略端口或端口为 '*' 时匹配任意端口, path 中的 '*' 匹配任意字符序列
+// "<all_urls>" 匹配任意带 scheme 的 URI
+#[derive(Debug, Clone)]
+pub struct UriPattern {
+    scheme: Option<String>,
+    host: HostPattern,
+    port: Option<u16>,
+    path: String,
+    serialized: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HostPattern {
+    Any,
+    Exact(String),
+    // "*.example.com" 保存为 "example.com", 同时匹配其自身
+    Subdomains(String),
+}
+
+const ALL_URLS: &str = "<all_urls>";
+
+impl HostPattern {
+    pub fn matches(&self, host: &str) -> bool {
+        match self {
+            HostPattern::Any => true,
+            HostPattern::Exact(h) => h.eq_ignore_ascii_case(host),
+            HostPattern::Subdomains(d) => {
+                if host.len() < d.len() {
+                    return false;
+                }
+                let (prefix, suffix) = host.split_at(host.len() - d.len());
+                suffix.eq_ignore_ascii_case(d) && (prefix.is_empty() || prefix.ends_with('.'))
+            }
+        }
+    }
+}
+
+impl UriPattern {
+    pub fn as_str(&self) -> &str {
+        self.serialized.as_str()
+    }
+
+    pub fn host(&self) -> &HostPattern {
+        &self.host
+    }
+
+    pub fn matches(&self, uri: &URI) -> bool {
+        let scheme = match uri.scheme() {
+            Some(s) => s,
+            None => return false,
+        };
+        if self.scheme.as_ref().is_some_and(|s| !s.eq_ignore_ascii_case(scheme.as_str())) {
+            return false;
+        }
+        let host = uri.host().map(|h| h.as_str()).unwrap_or("");
+        if !self.host.matches(host) {
+            return false;
+        }
+        if let Some(port) = self.port {
+            // 没有端口时使用 scheme 的默认端口
+            let actual = uri.port().map(|p| p.as_u16()).or_else(|| scheme.default_port());
+            if actual != Some(port) {
+                return false;
+            }
+        }
+        let path = uri.path().map(|p| p.as_str()).unwrap_or("/");
+        let path = if path.is_empty() { "/" } else { path };
+        match uri.query() {
+            Some(q) => glob(&self.path, &format!("{}?{}", path, q)),
+            None => glob(&self.path, path),
+        }
+    }
+
+    pub fn matches_str(&self, s: &str) -> Result<bool> {
+        Ok(self.matches(&URI::decode(s)?))
+    }
+}
+
+impl Parser for UriPattern {
+    fn decode(s: &str) -> Result<Self> {
+        let invalid = |reason: &str| Error::Invalid(format!("{} in uri pattern \'{}\'", reason, s));
+        if s == ALL_URLS {
+            return Ok(UriPattern {
+                scheme: None,
+                host: HostPattern::Any,
+                port: None,
+                path: "*".to_string(),
+                serialized: s.to_string(),
+            });
+        }
+        let (scheme, rest) = s.split_once("://").ok_or_else(|| invalid("missing \"://\""))?;
+        let scheme = match scheme {
+            "*" => None,
+            sch if sch.as_bytes().first().is_some_and(|c| c.is_ascii_alphabetic())
+                && sch.bytes().all(|c| c.is_ascii_alphanumeric() || matches!(c, b'+' | b'-' | b'.')) =>
+            {
+                Some(sch.to_ascii_lowercase())
+            }
+            _ => return Err(invalid("invalid scheme")),
+        };
+        let slash = rest.find('/').ok_or_else(|| invalid("missing path"))?;
+        let (authority, path) = rest.split_at(slash);
+        // ip-literal 中的 ':' 不是端口分隔符
+        let port_at = match authority.rfind(']') {
+            Some(i) => authority[i..].find(':').map(|j| i + j),
+            None => authority.find(':'),
+        };
+        let (host, port) = match port_at {
+            Some(i) => (&authority[..i], Some(&authority[i + 1..])),
+            None => (authority, None),
+        };
+        let port = match port {
+            None | Some("*") => None,
+            Some(p) => Some(p.parse::<u16>().map_err(|_| invalid("invalid port"))?),
+        };
+        let host = match host {
+            "*" => HostPattern::Any,
+            h if h.contains('*') => match h.strip_prefix("*.") {
+                Some(d) if !d.is_empty() && !d.contains('*') => HostPattern::Subdomains(d.to_ascii_lowercase()),
+                _ => return Err(invalid("'*' must be the whole host or its first label")),
+            },
+            "" if scheme.as_deref() != Some("file") => return Err(invalid("empty host")),
+            h => HostPattern::Exact(h.to_ascii_lowercase()),
+        };
+        Ok(UriPattern {
+            scheme,
+            host,
+            port,
+            path: path.to_string(),
+            serialized: s.to_string(),
+        })
+    }
+
+    fn encode(&self) -> Result<String> {
+        Ok(self.serialized.clone())
+    }
+}
+
+impl_str_conv!(UriPattern);
+
+// '*' 匹配任意字符序列, 其余字符按字节比较
+fn glob(pattern: &str, s: &str) -> bool {
+    let (p, s) = (pattern.as_bytes(), s.as_bytes());
+    let (mut pi, mut si) = (0, 0);
+    // 最近一个 '*' 的位置及其匹配到的位置
+    let mut star: Option<(usize, usize)> = None;
+    while si < s.len() {
+        if pi < p.len() && p[pi] == b'*' {
+            star = Some((pi, si));
+            pi += 1;
+        } else if pi < p.len() && p[pi] == s[si] {
+            pi += 1;
+            si += 1;
+        } else if let Some((sp, ss)) = star {
+            pi = sp + 1;
+            si = ss + 1;
+            star = Some((sp, ss + 1));
+        } else {
+            return false;
+        }
+    }
+    p[pi..].iter().all(|c| *c == b'*')
+}
+
+// 预先按 host 建立索引, 用于同时匹配大量模式
+#[derive(Debug, Clone, Default)]
+pub struct UriPatternSet {
+    patterns: Vec<UriPattern>,
+    // 小写 host 或域名后缀 -> 模式序号
+    exact: BTreeMap<String, Vec<usize>>,
+    subdomains: BTreeMap<String, Vec<usize>>,
+    any: Vec<usize>,
+}
+
+impl UriPatternSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&mut self, pattern: UriPattern) -> &mut Self {
+        let i = self.patterns.len();
+        match pattern.host() {
+            HostPattern::Any => self.any.push(i),
+            HostPattern::Exact(h) => self.exact.entry(h.clone()).or_default().push(i),
+            HostPattern::Subdomains(d) => self.subdomains.entry(d.clone()).or_default().push(i),
+        }
+        self.patterns.push(pattern);
+        self
+    }
+
+    pub fn add_str(&mut self, s: &str) -> Result<&mut Self> {
+        Ok(self.add(UriPattern::decode(s)?))
+    }
+
+    pub fn len(&self) -> usize {
+        self.patterns.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.patterns.is_empty()
+    }
+
+    pub fn get(&self, i: usize) -> Option<&UriPattern> {
+        self.patterns.get(i)
+    }
+
+    pub fn is_match(&self, uri: &URI) -> bool {
+        self.candidates(uri).into_iter().any(|i| self.patterns[i].matches(uri))
+    }
+
+    // 全部匹配的模式序号, 按添加顺序
+    pub fn matches(&self, uri: &URI) -> Vec<usize> {
+        let mut found: Vec<usize> = self.candidates(uri).into_iter().filter(|i| self.patterns[*i].matches(uri)).collect();
+        found.sort_unstable();
+        found
+    }
+
+    fn candidates(&self, uri: &URI) -> Vec<usize> {
+        let host = uri.host().map(|h| h.as_str().to_ascii_lowercase()).unwrap_or_default();
+        let mut found = self.any.clone();
+        if let Some(v) = self.exact.get(&host) {
+            found.extend(v);
+        }
+        // host 自身及其各级父域名
+        let mut domain = host.as_str();
+        loop {
+            if let Some(v) = self.subdomains.get(domain) {
+                found.extend(v);
+            }
+            match domain.split_once('.') {
+                Some((_, parent)) => domain = parent,
+                None => break,
+            }
+        }
+        found
+    }
+}
@@ -1,85 +1,201 @@
-use std::collections::{HashMap, HashSet};
-use std::iter::Map;
+use alloc::format;
+use alloc::vec;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::iter::Map;
 use crate::error::Error::Path;
-use super::encoder::{Encoder, Decoder};
+use super::encoder::{normalize, percent_decode, percent_encode, Allowed, Encoder, Decoder};
 use super::Parser;
 use super::error::{Result, Error};
 
-type QData = HashMap<String, HashSet<String>>;
+// 值为 None 表示该项没有 '=', 如 "?flag"
+type QData = Vec<(String, Option<String>)>;
 
-#[derive(Debug)]
-pub struct Query(QData);
+#[derive(Debug, Clone)]
+pub struct Query {
+    pairs: QData,
+    // 解码得到的各项编码形式, 修改过或新加的项为 None
+    raw: Vec<Option<String>>,
+    serialized: String,
+}
+
+impl Default for Query {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 impl Query {
     pub fn new() -> Self {
-        Query(HashMap::new())
+        Query {
+            pairs: Vec::new(),
+            raw: Vec::new(),
+            serialized: String::new(),
+        }
+    }
+
+    pub fn from_pairs(pairs: QData) -> Self {
+        let mut q = Query {
+            raw: vec![None; pairs.len()],
+            pairs,
+            serialized: String::new(),
+        };
+        q.serialize();
+        q
     }
 
     pub fn set<T: Into<String>>(&mut self, key: String, val: T) -> &mut Self {
-        insert(&mut self.0, key, val);
+        insert(&mut self.pairs, key, val);
+        self.raw.push(None);
+        self.serialize();
         self
     }
 
     pub fn set_str(&mut self, s: &str) -> Result<&mut Self> {
         let (k, v) = parse_str(s)?;
-        insert(&mut self.0, k, v);
+        insert(&mut self.pairs, k, v);
+        self.raw.push(None);
+        self.serialize();
         Ok(self)
     }
+
+    // 替换该键已有的全部值
+    pub fn replace<T: Into<String>>(&mut self, key: &str, val: T) -> &mut Self {
+        self.remove_key(key);
+        self.pairs.push((key.to_string(), Some(val.into())));
+        self.raw.push(None);
+        self.serialize();
+        self
+    }
+
+    pub fn remove(&mut self, key: &str) -> &mut Self {
+        self.remove_key(key);
+        self.serialize();
+        self
+    }
+
+    pub fn as_str(&self) -> &str {
+        self.serialized.as_str()
+    }
+
+    // 没有 '=' 的项返回空字符串
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.pairs.iter().find(|(k, _)| k == key).map(|(_, v)| v.as_deref().unwrap_or(""))
+    }
+
+    pub fn get_all(&self, key: &str) -> Vec<&str> {
+        self.pairs.iter().filter(|(k, _)| k == key).map(|(_, v)| v.as_deref().unwrap_or("")).collect()
+    }
+
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.pairs.iter().any(|(k, _)| k == key)
+    }
+
+    pub fn pairs(&self) -> &[(String, Option<String>)] {
+        self.pairs.as_slice()
+    }
+
+    pub fn len(&self) -> usize {
+        self.pairs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pairs.is_empty()
+    }
+
+    // 将指定键的值替换为掩码
+    pub fn redacted(&self, keys: &[String]) -> Self {
+        let mut q = self.clone();
+        for ((k, v), raw) in q.pairs.iter_mut().zip(q.raw.iter_mut()) {
+            if keys.contains(k) && v.is_some() {
+                *v = Some(crate::REDACTED.to_string());
+                *raw = None;
+            }
+        }
+        q.serialize();
+        q
+    }
+
+    fn remove_key(&mut self, key: &str) {
+        let mut i = 0;
+        while i < self.pairs.len() {
+            if self.pairs[i].0 == key {
+                self.pairs.remove(i);
+                self.raw.remove(i);
+            } else {
+                i += 1;
+            }
+        }
+    }
+
+    // 未修改的项保持解码时的编码形式
+    fn serialize(&mut self) {
+        let allowed = item_allowed();
+        let mut buf = Vec::<String>::new();
+        for ((k, v), raw) in self.pairs.iter().zip(self.raw.iter()) {
+            if let Some(raw) = raw {
+                buf.push(raw.clone());
+                continue;
+            }
+            match v {
+                Some(v) => buf.push(format!("{}={}", percent_encode(k, &allowed), percent_encode(v, &allowed))),
+                None => buf.push(percent_encode(k, &allowed)),
+            }
+        }
+        self.serialized = buf.join("&");
+    }
 }
 
 impl Parser for Query {
     fn decode(s: &str) -> Result<Self> {
-        let mut mp = HashMap::<String, HashSet<String>>::new();
-        let mut dec = Decoder::new(s);
-        dec.set_decode_pct();
-        dec.allowed().set_unreserved().set_subdelims().set([b':', b'@', b'/', b'?'].to_vec());
-        let qs = dec.decode()?;
-        for v in qs.split("&") {
-            let (k, v) = parse_str(v)?;
-            insert(&mut mp, k, v);
+        let mut allowed = item_allowed();
+        allowed.set(vec![b'&', b'=']);
+        let serialized = normalize(s, &allowed)?;
+        let mut pairs = Vec::new();
+        let mut raw = Vec::new();
+        // 先按 '&' 与 '=' 分隔再解码, 值中编码过的分隔符不会被拆开
+        if !serialized.is_empty() {
+            for item in serialized.split('&') {
+                match item.split_once('=') {
+                    Some((k, v)) => pairs.push((percent_decode(k), Some(percent_decode(v)))),
+                    None => pairs.push((percent_decode(item), None)),
+                }
+                raw.push(Some(item.to_string()));
+            }
         }
-        Ok(Query(mp))
+        Ok(Query { pairs, raw, serialized })
     }
 
     fn encode(&self) -> Result<String> {
-        let mut buf = Vec::<String>::new();
-        let mp = &self.0;
-        for (k, vl) in mp.iter() {
-            for v in vl {
-                buf.push(format!("{}={}", k, v))
-            }
-        }
-        let s = buf.join("&");
-        let mut enc = Encoder::new(s.as_str());
-        enc.set_encode_pct();
-        enc.allowed().set_unreserved().set_subdelims().set([b':', b'@', b'/', b'?'].to_vec());
-        let r = enc.encode()?;
-        Ok(s)
+        Ok(self.serialized.clone())
     }
 }
 
+impl_str_conv!(Query);
+
+// 键与值中的 '&' 和 '=' 需要编码
+const ITEM_CHARS: [u8; 13] = [b'!', b'$', b'\'', b'(', b')', b'*', b'+', b',', b';', b':', b'@', b'/', b'?'];
+
+fn item_allowed() -> Allowed {
+    let mut allowed = Allowed::new();
+    allowed.set_unreserved().set(ITEM_CHARS.to_vec());
+    allowed
+}
+
 fn parse_str(s: &str) -> Result<(String, String)> {
-    let a: Vec<String> = s.split("=").map(|s| s.to_string()).collect();
-    if a.len() != 2 {
-        return Err(Error::Path("invalid path item string".to_string()));
+    match s.split_once('=') {
+        Some((k, v)) => Ok((k.to_string(), v.to_string())),
+        None => Err(Error::Path("invalid path item string".to_string())),
     }
-    Ok((a[0].clone(), a[1].clone()))
 }
 
 fn insert<T: Into<String>>(q: &mut QData, k: String, v: T) -> &mut QData {
-    match q.get_mut(k.as_str()) {
-        None => {
-            let mut set = HashSet::new();
-            set.insert(v.into());
-            q.insert(k, set);
-        }
-        Some(set) => {
-            set.insert(v.into());
-        }
-    }
+    // 重复的键值对同样保留
+    q.push((k, Some(v.into())));
     q
 }
 
+#[cfg(test)]
 #[test]
 fn path_decode() {
     let s = "version=1.0&qlist=10&qlist=20";
@@ -90,6 +206,7 @@ fn path_decode() {
     }
 }
 
+#[cfg(test)]
 #[test]
 fn path_encode() {
     let mut q = Query::new();
@@ -0,0 +1,88 @@
+use alloc::format;
+use alloc::string::{String, ToString};
+use super::error::{Error, Result};
+use super::uri::URI;
+use super::Parser;
+
+// rfc3986 4.1 - 4.4 中的引用类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ReferenceKind {
+    // 含 scheme, 可以带 fragment
+    AbsoluteUri,
+    // 以 "//" 开头
+    NetworkPath,
+    // 以单个 "/" 开头
+    AbsolutePath,
+    // 不以 "/" 开头的相对路径, 包括只有 query 的情况
+    RelativePath,
+    // 空字符串或只有 fragment
+    SameDocument,
+}
+
+impl ReferenceKind {
+    pub fn is_relative(&self) -> bool {
+        !matches!(self, ReferenceKind::AbsoluteUri)
+    }
+}
+
+// 任意 URI-reference, 解析时记录引用类型
+#[derive(Debug, Clone)]
+pub struct UriReference {
+    uri: URI,
+    kind: ReferenceKind,
+}
+
+impl UriReference {
+    pub fn parse(s: &str) -> Result<Self> {
+        let uri = URI::decode(s)?;
+        let kind = uri.reference_kind();
+        Ok(UriReference { uri, kind })
+    }
+
+    // 只接受 relative-ref, 含 scheme 的 URI 会被拒绝
+    pub fn parse_relative(s: &str) -> Result<Self> {
+        let r = UriReference::parse(s)?;
+        if !r.kind.is_relative() {
+            return Err(Error::Invalid(format!("expected a relative reference, got an absolute uri \'{}\'", s)));
+        }
+        Ok(r)
+    }
+
+    pub fn kind(&self) -> ReferenceKind {
+        self.kind
+    }
+
+    pub fn is_relative(&self) -> bool {
+        self.kind.is_relative()
+    }
+
+    pub fn as_uri(&self) -> &URI {
+        &self.uri
+    }
+
+    pub fn into_uri(self) -> URI {
+        self.uri
+    }
+
+    pub fn as_str(&self) -> &str {
+        self.uri.as_str()
+    }
+}
+
+impl Parser for UriReference {
+    fn decode(s: &str) -> Result<Self> {
+        UriReference::parse(s)
+    }
+
+    fn encode(&self) -> Result<String> {
+        self.uri.encode()
+    }
+}
+
+impl From<UriReference> for URI {
+    fn from(value: UriReference) -> Self {
+        value.uri
+    }
+}
+
+impl_str_conv!(UriReference);
@@ -0,0 +1,176 @@
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use super::authority::Authority;
+use super::error::{Error, Result};
+use super::fragment::Fragment;
+use super::path::{remove_dot_segments, Path};
+use super::query::Query;
+use super::rpart::RPart;
+use super::scheme::Scheme;
+use super::uri::URI;
+use super::Parser;
+
+impl URI {
+    // 以 self 为基准解析引用, rfc3986 5.2.2
+    pub fn resolve(&self, reference: &URI) -> Result<URI> {
+        if self.scheme().is_none() {
+            return Err(Error::Invalid(format!("base uri must be absolute, \'{}\'", self)));
+        }
+        let base_path = path_str(self);
+        let ref_path = path_str(reference);
+        let (scheme, authority, path, query);
+        if reference.scheme().is_some() {
+            scheme = reference.scheme().cloned();
+            authority = reference.authority().cloned();
+            path = remove_dot_segments(ref_path);
+            query = reference.query().cloned();
+        } else {
+            if reference.authority().is_some() {
+                authority = reference.authority().cloned();
+                path = remove_dot_segments(ref_path);
+                query = reference.query().cloned();
+            } else {
+                if ref_path.is_empty() {
+                    path = base_path.to_string();
+                    query = reference.query().or(self.query()).cloned();
+                } else {
+                    if ref_path.starts_with('/') {
+                        path = remove_dot_segments(ref_path);
+                    } else {
+                        path = remove_dot_segments(merge(self, ref_path).as_str());
+                    }
+                    query = reference.query().cloned();
+                }
+                authority = self.authority().cloned();
+            }
+            scheme = self.scheme().cloned();
+        }
+        let fragment = reference.fragment().cloned();
+        assemble(scheme, authority, path.as_str(), query, fragment)
+    }
+
+    // 生成相对于 self 的最短引用, 使 self.resolve(&r) 与 target 等价
+    pub fn make_relative(&self, target: &URI) -> URI {
+        let same_scheme = match (self.scheme(), target.scheme()) {
+            (Some(a), Some(b)) => a.as_str().eq_ignore_ascii_case(b.as_str()),
+            _ => false,
+        };
+        if !same_scheme {
+            return target.clone();
+        }
+        let network = || {
+            if target.authority().is_none() {
+                return target.clone();
+            }
+            let path = remove_dot_segments(path_str(target));
+            assemble(None, target.authority().cloned(), path.as_str(), target.query().cloned(), target.fragment().cloned()).unwrap_or_else(|_| target.clone())
+        };
+        if self.authority().map(|a| a.as_str()) != target.authority().map(|a| a.as_str()) {
+            return network();
+        }
+
+        // 结果无法还原为 target 时退回到 network-path 引用
+        match same_authority_ref(self, target) {
+            Ok(Some(r)) if self.resolve(&r).is_ok_and(|u| u.as_str() == resolved_str(target)) => r,
+            _ => network(),
+        }
+    }
+}
+
+// authority 相同时的相对引用, 不存在这样的引用时为 None
+fn same_authority_ref(base: &URI, target: &URI) -> Result<Option<URI>> {
+    let base_path = remove_dot_segments(path_str(base));
+    let target_path = remove_dot_segments(path_str(target));
+    let query = target.query().cloned();
+    let fragment = target.fragment().cloned();
+    if base_path == target_path {
+        let same_query = base.query().map(|q| q.as_str()) == target.query().map(|q| q.as_str());
+        if same_query || query.is_some() {
+            // 只有 query 或 fragment 不同
+            let q = if same_query { None } else { query };
+            return assemble(None, None, "", q, fragment).map(Some);
+        }
+        // 需要去掉 query 时只能用路径引用
+        return match last_segment_ref(target_path.as_str()) {
+            Some(p) => assemble(None, None, p.as_str(), None, fragment).map(Some),
+            None => Ok(None),
+        };
+    }
+    match relative_path(base_path.as_str(), target_path.as_str()) {
+        Some(p) => assemble(None, None, p.as_str(), query, fragment).map(Some),
+        None => Ok(None),
+    }
+}
+
+fn path_str(u: &URI) -> &str {
+    u.path().map(|p| p.as_str()).unwrap_or("")
+}
+
+// target 消除点段后的编码结果
+fn resolved_str(u: &URI) -> String {
+    match assemble(u.scheme().cloned(), u.authority().cloned(), remove_dot_segments(path_str(u)).as_str(), u.query().cloned(), u.fragment().cloned()) {
+        Ok(r) => r.to_string(),
+        Err(_) => u.to_string(),
+    }
+}
+
+// rfc3986 5.2.3
+fn merge(base: &URI, ref_path: &str) -> String {
+    let base_path = path_str(base);
+    if base.authority().is_some() && base_path.is_empty() {
+        return format!("/{}", ref_path);
+    }
+    match base_path.rfind('/') {
+        Some(i) => format!("{}{}", &base_path[..=i], ref_path),
+        None => ref_path.to_string(),
+    }
+}
+
+// 两个绝对路径之间的相对路径, 优先使用 "../"
+fn relative_path(base: &str, target: &str) -> Option<String> {
+    if !base.starts_with('/') || !target.starts_with('/') {
+        return None;
+    }
+    let base_dir: Vec<&str> = base[1..].split('/').collect();
+    let base_dir = &base_dir[..base_dir.len() - 1];
+    let target_segs: Vec<&str> = target[1..].split('/').collect();
+    let (target_dir, file) = target_segs.split_at(target_segs.len() - 1);
+    let common = base_dir.iter().zip(target_dir.iter()).take_while(|(a, b)| a == b).count();
+    let mut segs: Vec<&str> = vec![".."; base_dir.len() - common];
+    segs.extend_from_slice(&target_dir[common..]);
+    segs.push(file[0]);
+    let mut rel = segs.join("/");
+    // 空路径、以 '/' 开头或第一段含 ':' 时加上 "./"
+    if rel.is_empty() || rel.starts_with('/') || rel.split('/').next().is_some_and(|s| s.contains(':')) {
+        rel = format!("./{}", rel);
+    }
+    Some(rel)
+}
+
+fn last_segment_ref(path: &str) -> Option<String> {
+    if !path.starts_with('/') {
+        return None;
+    }
+    let last = &path[path.rfind('/')? + 1..];
+    if last.is_empty() {
+        return Some("./".to_string());
+    }
+    if last.contains(':') {
+        return Some(format!("./{}", last));
+    }
+    Some(last.to_string())
+}
+
+fn assemble(scheme: Option<Scheme>, authority: Option<Authority>, path: &str, query: Option<Query>, fragment: Option<Fragment>) -> Result<URI> {
+    let mut rpart = RPart::new();
+    rpart.set_authority(authority);
+    if !path.is_empty() {
+        rpart.set_path(Some(Path::decode(path)?));
+    }
+    let rpart = if rpart.as_str().is_empty() { None } else { Some(rpart) };
+    let u = URI::from_parts(scheme, rpart, query, fragment);
+    u.validate()?;
+    Ok(u)
+}
@@ -0,0 +1,203 @@
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use super::encoder::percent_decode;
+use super::error::{Error, Result};
+use super::path::Path;
+use super::Parser;
+
+// 路由模式, 如 "/users/:id/posts/*rest"
+// ":name" 匹配一个非空段, 值已解码
+// "*name" 匹配剩余的全部段, 只能出现在最后, 值保持编码形式, "%2F" 与 '/' 可以区分
+// 以 ':' 或 '*' 开头的普通段需写作 "%3A", "%2A"
+#[derive(Debug, Clone)]
+pub struct Router<T> {
+    root: Node<T>,
+    len: usize,
+}
+
+#[derive(Debug, Clone)]
+struct Node<T> {
+    // 键为解码后的段
+    statics: BTreeMap<String, Node<T>>,
+    param: Option<(String, Box<Node<T>>)>,
+    wildcard: Option<(String, T)>,
+    value: Option<T>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Params(Vec<(String, String)>);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Match<'a, T> {
+    pub value: &'a T,
+    pub params: Params,
+}
+
+enum Segment<'a> {
+    Static(String),
+    Param(&'a str),
+    Wildcard(&'a str),
+}
+
+impl<T> Default for Router<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Default for Node<T> {
+    fn default() -> Self {
+        Node {
+            statics: BTreeMap::new(),
+            param: None,
+            wildcard: None,
+            value: None,
+        }
+    }
+}
+
+impl Params {
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.0.iter().find(|(k, _)| k == name).map(|(_, v)| v.as_str())
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.0.iter().map(|(k, v)| (k.as_str(), v.as_str()))
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl<T> Router<T> {
+    pub fn new() -> Self {
+        Router {
+            root: Node::default(),
+            len: 0,
+        }
+    }
+
+    // 已注册的路由数
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    // 同一位置的参数名不同或路由重复时报错
+    pub fn insert(&mut self, pattern: &str, value: T) -> Result<&mut Self> {
+        let segments = parse_pattern(pattern)?;
+        let mut node = &mut self.root;
+        for (i, seg) in segments.iter().enumerate() {
+            node = match seg {
+                Segment::Static(s) => node.statics.entry(s.clone()).or_default(),
+                Segment::Param(name) => {
+                    let (existing, child) = node.param.get_or_insert_with(|| (name.to_string(), Box::default()));
+                    if existing != name {
+                        return Err(Error::Invalid(format!("parameter \':{}\' conflicts with \':{}\' in \'{}\'", name, existing, pattern)));
+                    }
+                    child
+                }
+                Segment::Wildcard(name) => {
+                    if i + 1 != segments.len() {
+                        return Err(Error::Invalid(format!("wildcard must be the last segment in \'{}\'", pattern)));
+                    }
+                    if node.wildcard.is_some() {
+                        return Err(Error::Invalid(format!("duplicate route \'{}\'", pattern)));
+                    }
+                    node.wildcard = Some((name.to_string(), value));
+                    self.len += 1;
+                    return Ok(self);
+                }
+            };
+        }
+        if node.value.is_some() {
+            return Err(Error::Invalid(format!("duplicate route \'{}\'", pattern)));
+        }
+        node.value = Some(value);
+        self.len += 1;
+        Ok(self)
+    }
+
+    // 优先级: 普通段 > 参数 > 通配, 前者失败时回溯
+    pub fn at(&self, path: &Path) -> Option<Match<'_, T>> {
+        let segments = split_segments(path.as_str())?;
+        let mut params = Vec::new();
+        let value = self.root.find(&segments, &mut params)?;
+        Some(Match {
+            value,
+            params: Params(params),
+        })
+    }
+
+    pub fn at_str(&self, path: &str) -> Result<Option<Match<'_, T>>> {
+        Ok(self.at(&Path::decode(path)?))
+    }
+}
+
+impl<T> Node<T> {
+    // segments 为未解码的段
+    fn find<'a>(&'a self, segments: &[&str], params: &mut Vec<(String, String)>) -> Option<&'a T> {
+        let (first, rest) = match segments.split_first() {
+            Some(s) => s,
+            None => return self.value.as_ref(),
+        };
+        let decoded = percent_decode(first);
+        if let Some(v) = self.statics.get(&decoded).and_then(|child| child.find(rest, params)) {
+            return Some(v);
+        }
+        if let Some((name, child)) = &self.param {
+            if !first.is_empty() {
+                params.push((name.clone(), decoded));
+                if let Some(v) = child.find(rest, params) {
+                    return Some(v);
+                }
+                params.pop();
+            }
+        }
+        let (name, value) = self.wildcard.as_ref()?;
+        params.push((name.clone(), segments.join("/")));
+        Some(value)
+    }
+}
+
+// 按编码后的 '/' 切分, 匹配时再解码, "%2F" 不会被当作分隔符
+fn split_segments(path: &str) -> Option<Vec<&str>> {
+    let rest = path.strip_prefix('/')?;
+    Some(rest.split('/').collect())
+}
+
+fn parse_pattern(pattern: &str) -> Result<Vec<Segment<'_>>> {
+    Path::decode(pattern)?;
+    let rest = match pattern.strip_prefix('/') {
+        Some(rest) => rest,
+        None => return Err(Error::Invalid(format!("route pattern must start with \'/\', \'{}\'", pattern))),
+    };
+    let mut segments = Vec::new();
+    for raw in rest.split('/') {
+        let seg = if let Some(name) = raw.strip_prefix(':') {
+            Segment::Param(name)
+        } else if let Some(name) = raw.strip_prefix('*') {
+            Segment::Wildcard(name)
+        } else {
+            Segment::Static(percent_decode(raw))
+        };
+        if let Segment::Param(name) | Segment::Wildcard(name) = seg {
+            if name.is_empty() || !name.bytes().all(|c| c.is_ascii_alphanumeric() || c == b'_') {
+                return Err(Error::Invalid(format!("invalid parameter name \'{}\' in \'{}\'", name, pattern)));
+            }
+        }
+        segments.push(seg);
+    }
+    Ok(segments)
+}
@@ -1,64 +1,106 @@
-use std::fmt::format;
+use alloc::fmt::format;
+use alloc::format;
+use alloc::string::{String, ToString};
 use crate::encoder::Decoder;
 use crate::Parser;
 use super::authority::Authority;
 use super::path::Path;
 use super::error::Result;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct RPart {
-    pub authority: Option<Authority>,
-    pub path: Option<Path>,
+    authority: Option<Authority>,
+    path: Option<Path>,
+    serialized: String,
 }
 
 impl RPart {
+    #[allow(clippy::new_without_default)]
     pub fn new() -> Self {
         RPart {
             authority: None,
             path: None,
+            serialized: String::new(),
         }
     }
+
+    pub fn authority(&self) -> Option<&Authority> {
+        self.authority.as_ref()
+    }
+
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_ref()
+    }
+
+    pub fn set_authority(&mut self, authority: Option<Authority>) -> &mut Self {
+        self.authority = authority;
+        self.serialize();
+        self
+    }
+
+    // 修改 authority 后重新生成编码结果
+    pub fn update_authority<F: FnOnce(&mut Authority)>(&mut self, f: F) -> &mut Self {
+        if let Some(a) = self.authority.as_mut() {
+            f(a);
+        }
+        self.serialize();
+        self
+    }
+
+    pub fn set_path(&mut self, path: Option<Path>) -> &mut Self {
+        self.path = path;
+        self.serialize();
+        self
+    }
+
+    pub fn as_str(&self) -> &str {
+        self.serialized.as_str()
+    }
+
+    pub fn redacted(&self) -> Self {
+        let mut r = self.clone();
+        r.authority = self.authority.as_ref().map(|a| a.redacted());
+        r.serialize();
+        r
+    }
+
+    fn serialize(&mut self) {
+        let len = self.authority.as_ref().map_or(0, |a| a.encoded_len() + 2)
+            + self.path.as_ref().map_or(0, |p| p.encoded_len());
+        let mut buf = String::with_capacity(len);
+        if let Some(auth) = &self.authority {
+            buf.push_str("//");
+            buf.push_str(auth.as_str());
+        }
+        if let Some(path) = &self.path {
+            buf.push_str(path.as_str())
+        }
+        self.serialized = buf;
+    }
 }
 
 impl Parser for RPart {
+    // hier-part / relative-part, "//" 之后到下一个 '/' 为 authority
     fn decode(s: &str) -> Result<Self> {
         let mut rpart = RPart::new();
-        let mut path = String::new();
-        let mut author = String::new();
-        if s.starts_with("//") {
-            author = s.trim_start_matches("//").to_string();
-            let auth_end = author.find("/");
-            if let Some(index) = auth_end {
-                let t = author.clone();
-                let (a, p) = t.split_at(index);
-                author = a.to_string();
-                path = p.to_string();
-                path = format!("{}", path);
+        let path = match s.strip_prefix("//") {
+            Some(rest) => {
+                let end = rest.find('/').unwrap_or(rest.len());
+                rpart.authority = Some(Authority::decode(&rest[..end])?);
+                &rest[end..]
             }
-        } else {
-            path = s.to_string()
-        }
-        if author.len() > 0 {
-            let auth_r = Authority::decode(author.as_str())?;
-            rpart.authority = Some(auth_r)
-        }
-        if path.len() > 0 {
-            let path_r = Path::decode(path.as_str())?;
-            rpart.path = Some(path_r)
+            None => s,
+        };
+        if !path.is_empty() {
+            rpart.path = Some(Path::decode(path)?)
         }
+        rpart.serialize();
         Ok(rpart)
     }
 
     fn encode(&self) -> Result<String> {
-        let mut buf = String::new();
-        if let Some(auth) = &self.authority {
-            let r = auth.encode()?;
-            buf = format!("//{}", r);
-        }
-        if let Some(path) = &self.path {
-            let r = path.encode()?;
-            buf.push_str(r.as_str())
-        }
-        Ok(buf)
+        Ok(self.serialized.clone())
     }
-}
\ No newline at end of file
+}
+
+impl_str_conv!(RPart);
\ No newline at end of file
@@ -1,4 +1,7 @@
-use std::str::FromStr;
+use alloc::format;
+use alloc::vec;
+use alloc::string::{String, ToString};
+use core::str::FromStr;
 use super::encoder::{Decoder, Encoder};
 use super::error::{Error, Result};
 use super::Parser;
@@ -7,9 +10,29 @@ use super::Parser;
 #[derive(Debug, Clone)]
 pub struct Scheme(String);
 
+impl Scheme {
+    pub fn as_str(&self) -> &str {
+        self.0.as_str()
+    }
+
+    // 常见 scheme 的默认端口, scheme 不区分大小写
+    pub fn default_port(&self) -> Option<u16> {
+        default_port(self.0.as_str())
+    }
+}
+
+pub(crate) fn default_port(scheme: &str) -> Option<u16> {
+    match scheme.to_ascii_lowercase().as_str() {
+        "http" | "ws" => Some(80),
+        "https" | "wss" => Some(443),
+        "ftp" => Some(21),
+        _ => None,
+    }
+}
+
 impl Parser for Scheme {
     fn decode(s: &str) -> Result<Self> {
-        if !s.as_bytes()[0].is_ascii_alphabetic() {
+        if !s.as_bytes().first().is_some_and(|c| c.is_ascii_alphabetic()) {
             return Err(Error::Encode("scheme must start with alphabetic".to_string()));
         }
         let mut dec = Decoder::new(s);
@@ -19,12 +42,8 @@ impl Parser for Scheme {
     }
 
     fn encode(&self) -> Result<String> {
-        if !self.0.as_bytes()[0].is_ascii_alphabetic() {
-            return Err(Error::Encode("scheme must start with alphabetic".to_string()));
-        }
-        let mut enc = Encoder::new(self.0.as_str());
-        enc.allowed().set_alphanum().set(vec![b'+', b'-', b'.']);
-        let rst = enc.encode()?;
-        Ok(rst)
+        Ok(self.0.clone())
     }
-}
\ No newline at end of file
+}
+
+impl_str_conv!(Scheme);
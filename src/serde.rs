@@ -0,0 +1,115 @@
+use alloc::string::String;
+use ::serde::de::Error as DeError;
+use ::serde::{Deserialize, Deserializer, Serialize, Serializer};
+use super::authority::{Authority, Host, Port, UserInfo};
+use super::fragment::Fragment;
+use super::path::Path;
+use super::query::Query;
+use super::rpart::RPart;
+use super::scheme::Scheme;
+use super::uri::URI;
+use super::Parser;
+
+// 默认以编码后的字符串形式序列化
+macro_rules! impl_serde_str {
+    ($($t:ty),*) => {
+        $(
+            impl Serialize for $t {
+                fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                    serializer.serialize_str(self.as_str())
+                }
+            }
+
+            impl<'de> Deserialize<'de> for $t {
+                fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                    let s = String::deserialize(deserializer)?;
+                    <$t as Parser>::decode(s.as_str()).map_err(D::Error::custom)
+                }
+            }
+        )*
+    };
+}
+
+impl_serde_str!(URI, RPart, Authority, UserInfo, Host, Port, Scheme, Path, Query, Fragment);
+
+// 结构化形式, 通过 #[serde(with = "uri::serde::structured")] 使用
+// 除 query 为解码后的键值对外, 其余字段均为编码后的字符串
+pub mod structured {
+    use alloc::string::{String, ToString};
+    use alloc::vec::Vec;
+    use ::serde::de::Error as DeError;
+    use ::serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use crate::query::Query;
+    use crate::uri::URI;
+
+    #[derive(Serialize, Deserialize)]
+    struct Parts {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        scheme: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        userinfo: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        host: Option<String>,
+        // null 表示空端口, 如 "http://h:/"
+        #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "present")]
+        port: Option<Option<u16>>,
+        #[serde(default)]
+        path: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        query: Option<Vec<(String, Option<String>)>>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        fragment: Option<String>,
+    }
+
+    pub fn serialize<S: Serializer>(u: &URI, serializer: S) -> Result<S::Ok, S::Error> {
+        let parts = Parts {
+            scheme: u.scheme().map(|s| s.to_string()),
+            userinfo: u.user_info().map(|s| s.to_string()),
+            host: u.host().map(|s| s.to_string()),
+            port: match u.authority() {
+                Some(a) if a.has_empty_port() => Some(None),
+                _ => u.port().map(|p| Some(p.as_u16())),
+            },
+            path: u.path().map(|p| p.to_string()).unwrap_or_default(),
+            query: u.query().map(|q| q.pairs().to_vec()),
+            fragment: u.fragment().map(|s| s.to_string()),
+        };
+        parts.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<URI, D::Error> {
+        let parts = Parts::deserialize(deserializer)?;
+        let mut b = URI::builder();
+        if let Some(s) = &parts.scheme {
+            b.scheme(s);
+        }
+        if let Some(s) = &parts.userinfo {
+            b.user_info(s);
+        }
+        if let Some(s) = &parts.host {
+            b.host(s);
+        }
+        if let Some(Some(p)) = parts.port {
+            b.port(p);
+        }
+        if !parts.path.is_empty() {
+            b.path(parts.path.as_str());
+        }
+        if let Some(pairs) = parts.query {
+            b.query(Query::from_pairs(pairs).as_str());
+        }
+        if let Some(s) = &parts.fragment {
+            b.fragment(s);
+        }
+        let mut u = b.build().map_err(D::Error::custom)?;
+        if let Some(None) = parts.port {
+            u.set_empty_port().map_err(D::Error::custom)?;
+        }
+        Ok(u)
+    }
+
+    // 字段存在时即为 Some, 使 null 与缺省可以区分
+    fn present<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Option<u16>>, D::Error> {
+        Option::<u16>::deserialize(deserializer).map(Some)
+    }
+}
@@ -0,0 +1,101 @@
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::ToString;
+use alloc::vec::Vec;
+use super::encoder::{Allowed, GEN_DELIMS, SUB_DELIMS, UNRESERVED};
+use super::error::{Error, Result};
+use super::target::RequestTarget;
+use super::Parser;
+
+// 请求行中 request-target 默认的最大长度
+pub const DEFAULT_MAX_LEN: usize = 8192;
+
+// URI 中可以出现的全部字符
+const URI_CHARS: Allowed = UNRESERVED.union(SUB_DELIMS).union(GEN_DELIMS).with(b"%");
+
+#[derive(Debug)]
+pub enum Status {
+    NeedMore,
+    // consumed 为本次输入中属于 request-target 的字节数, 结束符位于 chunk[consumed]
+    Complete {
+        target: Box<RequestTarget>,
+        consumed: usize,
+    },
+}
+
+// 可分段输入的 request-target 解析器, 遇到 SP, CR 或 LF 时结束
+#[derive(Debug, Clone)]
+pub struct TargetParser {
+    buf: Vec<u8>,
+    max_len: usize,
+}
+
+impl Default for TargetParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TargetParser {
+    pub fn new() -> Self {
+        TargetParser {
+            buf: Vec::new(),
+            max_len: DEFAULT_MAX_LEN,
+        }
+    }
+
+    pub fn set_max_len(&mut self, max_len: usize) -> &mut Self {
+        self.max_len = max_len;
+        self
+    }
+
+    pub fn max_len(&self) -> usize {
+        self.max_len
+    }
+
+    // 已缓存的字节数
+    pub fn len(&self) -> usize {
+        self.buf.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.buf.is_empty()
+    }
+
+    pub fn reset(&mut self) {
+        self.buf.clear();
+    }
+
+    // 出错或完成后解析器被重置, 可以继续解析下一个请求
+    pub fn feed(&mut self, chunk: &[u8]) -> Result<Status> {
+        for (i, c) in chunk.iter().enumerate() {
+            if matches!(c, b' ' | b'\r' | b'\n') {
+                let target = Box::new(self.complete()?);
+                return Ok(Status::Complete { target, consumed: i });
+            }
+            if !URI_CHARS.contains(*c) {
+                let pos = self.buf.len();
+                self.reset();
+                return Err(Error::Decode(format!("invalid byte 0x{:02X} at {} in request-target", c, pos)));
+            }
+            if self.buf.len() >= self.max_len {
+                self.reset();
+                return Err(Error::Decode(format!("request-target exceeds {} bytes", self.max_len)));
+            }
+            self.buf.push(*c);
+        }
+        Ok(Status::NeedMore)
+    }
+
+    // 输入结束且没有结束符时调用
+    pub fn finish(&mut self) -> Result<RequestTarget> {
+        self.complete()
+    }
+
+    fn complete(&mut self) -> Result<RequestTarget> {
+        let buf = core::mem::take(&mut self.buf);
+        // 只缓存了 ascii 字符
+        let s = core::str::from_utf8(&buf).map_err(|e| Error::Decode(e.to_string()))?;
+        RequestTarget::decode(s)
+    }
+}
@@ -0,0 +1,209 @@
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::{String, ToString};
+use super::authority::Authority;
+use super::error::{Error, Result};
+use super::path::Path;
+use super::query::Query;
+use super::uri::URI;
+use super::Parser;
+
+// request-target 的四种形式, rfc9112 3.2
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TargetForm {
+    // "/path?query"
+    Origin,
+    // "http://host/path", 发往代理的请求
+    Absolute,
+    // "host:port", 仅用于 CONNECT
+    Authority,
+    // "*", 仅用于 OPTIONS
+    Asterisk,
+}
+
+#[derive(Debug, Clone)]
+pub enum TargetKind {
+    // path 总是以 '/' 开头
+    Origin { path: Path, query: Option<Query> },
+    Absolute(Box<URI>),
+    Authority(Authority),
+    Asterisk,
+}
+
+#[derive(Debug, Clone)]
+pub struct RequestTarget {
+    kind: TargetKind,
+    serialized: String,
+}
+
+impl RequestTarget {
+    pub fn new(kind: TargetKind) -> Result<Self> {
+        match &kind {
+            TargetKind::Origin { path, .. } if !path.as_str().starts_with('/') => {
+                return Err(Error::Invalid(format!("origin-form path must start with \'/\', \'{}\'", path)));
+            }
+            TargetKind::Absolute(u) if !u.is_absolute() => {
+                return Err(Error::Invalid(format!("absolute-form requires an absolute uri, \'{}\'", u)));
+            }
+            TargetKind::Authority(a) if a.user_info().is_some() || a.port().is_none() => {
+                return Err(Error::Invalid(format!("authority-form must be host:port, \'{}\'", a)));
+            }
+            _ => {}
+        }
+        let mut t = RequestTarget {
+            kind,
+            serialized: String::new(),
+        };
+        t.serialize();
+        Ok(t)
+    }
+
+    pub fn asterisk() -> Self {
+        RequestTarget {
+            kind: TargetKind::Asterisk,
+            serialized: "*".to_string(),
+        }
+    }
+
+    pub fn kind(&self) -> &TargetKind {
+        &self.kind
+    }
+
+    pub fn form(&self) -> TargetForm {
+        match self.kind {
+            TargetKind::Origin { .. } => TargetForm::Origin,
+            TargetKind::Absolute(_) => TargetForm::Absolute,
+            TargetKind::Authority(_) => TargetForm::Authority,
+            TargetKind::Asterisk => TargetForm::Asterisk,
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        self.serialized.as_str()
+    }
+
+    // 重建目标 URI, rfc9112 3.3, host 为 Host 头的值
+    // absolute-form 忽略 host, authority-form 与 asterisk-form 的路径为空
+    pub fn to_absolute(&self, scheme: &str, host: &str) -> Result<URI> {
+        let mut u = URI::new();
+        match &self.kind {
+            TargetKind::Absolute(abs) => return Ok(abs.as_ref().clone()),
+            TargetKind::Origin { path, query } => {
+                u.set_scheme(scheme)?.set_authority(host)?.set_path(path.as_str())?;
+                if let Some(q) = query {
+                    u.set_query(q.as_str())?;
+                }
+            }
+            TargetKind::Authority(a) => {
+                u.set_scheme(scheme)?.set_authority(a.as_str())?;
+            }
+            TargetKind::Asterisk => {
+                u.set_scheme(scheme)?.set_authority(host)?;
+            }
+        }
+        Ok(u)
+    }
+
+    // absolute-form 转为 origin-form 和 Host 头, userinfo 不会出现在 Host 中
+    pub fn to_origin(&self) -> Result<(RequestTarget, Authority)> {
+        let u = match &self.kind {
+            TargetKind::Absolute(u) => u,
+            _ => return Err(Error::Invalid(format!("expected an absolute-form target, \'{}\'", self))),
+        };
+        let mut host = match u.authority() {
+            Some(a) if !a.host().is_empty() => a.clone(),
+            _ => return Err(Error::Invalid(format!("absolute-form target has no host, \'{}\'", self))),
+        };
+        host.clear_userinfo();
+        let path = match u.path() {
+            Some(p) => p.clone(),
+            None => Path::decode("/")?,
+        };
+        let origin = RequestTarget::new(TargetKind::Origin {
+            path,
+            query: u.query().cloned(),
+        })?;
+        Ok((origin, host))
+    }
+
+    fn serialize(&mut self) {
+        self.serialized = match &self.kind {
+            TargetKind::Origin { path, query } => match query {
+                Some(q) => format!("{}?{}", path, q),
+                None => path.as_str().to_string(),
+            },
+            TargetKind::Absolute(u) => u.as_str().to_string(),
+            TargetKind::Authority(a) => a.as_str().to_string(),
+            TargetKind::Asterisk => "*".to_string(),
+        }
+    }
+}
+
+impl TryFrom<URI> for RequestTarget {
+    type Error = Error;
+
+    // 转为 absolute-form, 会丢弃 fragment
+    fn try_from(mut u: URI) -> Result<Self> {
+        if u.fragment().is_some() {
+            u.clear_fragment()?;
+        }
+        RequestTarget::new(TargetKind::Absolute(Box::new(u)))
+    }
+}
+
+impl Parser for RequestTarget {
+    fn decode(s: &str) -> Result<Self> {
+        let kind = match classify(s)? {
+            TargetForm::Origin => {
+                if s.contains('#') {
+                    return Err(Error::Decode(format!("request-target cannot contain a fragment, \'{}\'", s)));
+                }
+                let (path, query) = match s.split_once('?') {
+                    Some((p, q)) => (p, Some(Query::decode(q)?)),
+                    None => (s, None),
+                };
+                TargetKind::Origin {
+                    path: Path::decode(path)?,
+                    query,
+                }
+            }
+            TargetForm::Absolute => {
+                let u = URI::parse_absolute(s)?;
+                if u.fragment().is_some() {
+                    return Err(Error::Decode(format!("request-target cannot contain a fragment, \'{}\'", s)));
+                }
+                TargetKind::Absolute(Box::new(u))
+            }
+            TargetForm::Authority => TargetKind::Authority(Authority::decode(s)?),
+            TargetForm::Asterisk => TargetKind::Asterisk,
+        };
+        RequestTarget::new(kind)
+    }
+
+    fn encode(&self) -> Result<String> {
+        Ok(self.serialized.clone())
+    }
+}
+
+impl_str_conv!(RequestTarget);
+
+// 按形状区分, 无法得知请求方法, "host:port" 优先视为 authority-form
+fn classify(s: &str) -> Result<TargetForm> {
+    if s.is_empty() {
+        return Err(Error::Decode("request-target cannot be empty".to_string()));
+    }
+    if s == "*" {
+        return Ok(TargetForm::Asterisk);
+    }
+    if s.starts_with('/') {
+        return Ok(TargetForm::Origin);
+    }
+    if !s.contains("://") && !s.contains(['/', '?', '#', '@']) {
+        if let Some((_, port)) = s.rsplit_once(':') {
+            if !port.is_empty() && port.bytes().all(|c| c.is_ascii_digit()) {
+                return Ok(TargetForm::Authority);
+            }
+        }
+    }
+    Ok(TargetForm::Absolute)
+}
@@ -0,0 +1,436 @@
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use super::encoder::{percent_encode, Allowed, GEN_DELIMS, SUB_DELIMS, UNRESERVED};
+use super::error::{Error, Result};
+use super::uri::URI;
+use super::Parser;
+
+// 变量的取值, map 保持插入顺序
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Value {
+    String(String),
+    List(Vec<String>),
+    Map(Vec<(String, String)>),
+}
+
+pub type Vars = BTreeMap<String, Value>;
+
+impl Value {
+    // 空 list 和空 map 视为未定义, 空字符串是已定义的
+    fn is_defined(&self) -> bool {
+        match self {
+            Value::String(_) => true,
+            Value::List(l) => !l.is_empty(),
+            Value::Map(m) => !m.is_empty(),
+        }
+    }
+}
+
+impl From<&str> for Value {
+    fn from(value: &str) -> Self {
+        Value::String(value.to_string())
+    }
+}
+
+impl From<String> for Value {
+    fn from(value: String) -> Self {
+        Value::String(value)
+    }
+}
+
+impl From<Vec<&str>> for Value {
+    fn from(value: Vec<&str>) -> Self {
+        Value::List(value.into_iter().map(|s| s.to_string()).collect())
+    }
+}
+
+impl From<Vec<(&str, &str)>> for Value {
+    fn from(value: Vec<(&str, &str)>) -> Self {
+        Value::Map(value.into_iter().map(|(k, v)| (k.to_string(), v.to_string())).collect())
+    }
+}
+
+// rfc6570 2.2
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Operator {
+    Simple,
+    Reserved,
+    Fragment,
+    Label,
+    PathSegment,
+    PathParam,
+    Query,
+    QueryContinuation,
+}
+
+impl Operator {
+    fn from_byte(c: u8) -> Option<Self> {
+        match c {
+            b'+' => Some(Operator::Reserved),
+            b'#' => Some(Operator::Fragment),
+            b'.' => Some(Operator::Label),
+            b'/' => Some(Operator::PathSegment),
+            b';' => Some(Operator::PathParam),
+            b'?' => Some(Operator::Query),
+            b'&' => Some(Operator::QueryContinuation),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Operator::Simple => "",
+            Operator::Reserved => "+",
+            Operator::Fragment => "#",
+            Operator::Label => ".",
+            Operator::PathSegment => "/",
+            Operator::PathParam => ";",
+            Operator::Query => "?",
+            Operator::QueryContinuation => "&",
+        }
+    }
+
+    // 附录 A 中的 first, sep, named, ifemp, allow
+    pub(crate) fn first(&self) -> &'static str {
+        match self {
+            Operator::Simple | Operator::Reserved => "",
+            _ => self.as_str(),
+        }
+    }
+
+    pub(crate) fn sep(&self) -> &'static str {
+        match self {
+            Operator::Simple | Operator::Reserved | Operator::Fragment => ",",
+            Operator::Query | Operator::QueryContinuation => "&",
+            _ => self.as_str(),
+        }
+    }
+
+    pub(crate) fn named(&self) -> bool {
+        matches!(self, Operator::PathParam | Operator::Query | Operator::QueryContinuation)
+    }
+
+    fn ifemp(&self) -> &'static str {
+        match self {
+            Operator::Query | Operator::QueryContinuation => "=",
+            _ => "",
+        }
+    }
+
+    pub(crate) fn allow_reserved(&self) -> bool {
+        matches!(self, Operator::Reserved | Operator::Fragment)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Modifier {
+    None,
+    // 取前 n 个字符, 1 - 9999
+    Prefix(u16),
+    Explode,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VarSpec {
+    pub name: String,
+    pub modifier: Modifier,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Expression {
+    pub operator: Operator,
+    pub vars: Vec<VarSpec>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Part {
+    // 已编码的字面量
+    Literal(String),
+    Expression(Expression),
+}
+
+#[derive(Debug, Clone)]
+pub struct Template {
+    parts: Vec<Part>,
+    serialized: String,
+}
+
+impl Template {
+    pub fn parts(&self) -> &[Part] {
+        &self.parts
+    }
+
+    pub fn as_str(&self) -> &str {
+        self.serialized.as_str()
+    }
+
+    // 模板中出现的全部变量名, 按出现顺序
+    pub fn variables(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = Vec::new();
+        for part in &self.parts {
+            if let Part::Expression(e) = part {
+                for v in &e.vars {
+                    if !names.contains(&v.name.as_str()) {
+                        names.push(v.name.as_str());
+                    }
+                }
+            }
+        }
+        names
+    }
+
+    pub fn expand(&self, vars: &Vars) -> String {
+        let mut buf = String::new();
+        for part in &self.parts {
+            match part {
+                Part::Literal(s) => buf.push_str(s),
+                Part::Expression(e) => expand_expression(e, vars, &mut buf),
+            }
+        }
+        buf
+    }
+
+    pub fn expand_uri(&self, vars: &Vars) -> Result<URI> {
+        URI::decode(self.expand(vars).as_str())
+    }
+}
+
+impl Parser for Template {
+    fn decode(s: &str) -> Result<Self> {
+        let mut parts = Vec::new();
+        let mut literal = String::new();
+        let mut i = 0;
+        while i < s.len() {
+            let rest = &s[i..];
+            if rest.starts_with('{') {
+                let end = match rest.find('}') {
+                    Some(end) => end,
+                    None => return Err(Error::Decode(format!("unclosed expression at {} in template \'{}\'", i, s))),
+                };
+                if !literal.is_empty() {
+                    parts.push(Part::Literal(core::mem::take(&mut literal)));
+                }
+                parts.push(Part::Expression(parse_expression(&rest[1..end], i + 1, s)?));
+                i += end + 1;
+                continue;
+            }
+            let ch = rest.chars().next().unwrap_or_default();
+            i += match literal_char(rest, ch) {
+                Some(LiteralChar::Copy(len)) => {
+                    literal.push_str(&rest[..len]);
+                    len
+                }
+                Some(LiteralChar::Encode) => {
+                    let len = ch.len_utf8();
+                    literal.push_str(percent_encode(&rest[..len], &Allowed::new()).as_str());
+                    len
+                }
+                None => return Err(Error::Decode(format!("invalid character \'{}\' at {} in template \'{}\'", ch, i, s))),
+            };
+        }
+        if !literal.is_empty() {
+            parts.push(Part::Literal(literal));
+        }
+        Ok(Template {
+            parts,
+            serialized: s.to_string(),
+        })
+    }
+
+    fn encode(&self) -> Result<String> {
+        Ok(self.serialized.clone())
+    }
+}
+
+impl_str_conv!(Template);
+
+enum LiteralChar {
+    // 原样复制的字节数
+    Copy(usize),
+    Encode,
+}
+
+// rfc6570 2.1, 非 ascii 字符在展开时编码
+fn literal_char(rest: &str, ch: char) -> Option<LiteralChar> {
+    if ch == '%' {
+        let bs = rest.as_bytes();
+        return match bs.get(1..3) {
+            Some(h) if h.iter().all(|c| c.is_ascii_hexdigit()) => Some(LiteralChar::Copy(3)),
+            _ => None,
+        };
+    }
+    if !ch.is_ascii() {
+        return Some(LiteralChar::Encode);
+    }
+    // rfc6570 2.1, 字面量中不允许 '\''
+    if ch != '\'' && reserved_allowed().contains(ch as u8) {
+        return Some(LiteralChar::Copy(1));
+    }
+    None
+}
+
+pub(crate) fn reserved_allowed() -> Allowed {
+    UNRESERVED.union(GEN_DELIMS).union(SUB_DELIMS)
+}
+
+fn parse_expression(body: &str, offset: usize, s: &str) -> Result<Expression> {
+    let bs = body.as_bytes();
+    let (operator, list) = match bs.first().and_then(|c| Operator::from_byte(*c)) {
+        Some(op) => (op, &body[1..]),
+        // 保留给将来扩展的运算符
+        None if matches!(bs.first(), Some(b'=' | b',' | b'!' | b'@' | b'|')) => {
+            return Err(Error::Decode(format!("reserved operator at {} in template \'{}\'", offset, s)));
+        }
+        None => (Operator::Simple, body),
+    };
+    let mut vars = Vec::new();
+    for spec in list.split(',') {
+        let (name, modifier) = if let Some(name) = spec.strip_suffix('*') {
+            (name, Modifier::Explode)
+        } else if let Some((name, len)) = spec.split_once(':') {
+            let valid = !len.is_empty() && len.len() <= 4 && !len.starts_with('0') && len.bytes().all(|c| c.is_ascii_digit());
+            if !valid {
+                return Err(Error::Decode(format!("invalid prefix \'{}\' at {} in template \'{}\'", len, offset, s)));
+            }
+            (name, Modifier::Prefix(len.parse::<u16>()?))
+        } else {
+            (spec, Modifier::None)
+        };
+        if !is_varname(name) {
+            return Err(Error::Decode(format!("invalid variable name \'{}\' at {} in template \'{}\'", name, offset, s)));
+        }
+        vars.push(VarSpec {
+            name: name.to_string(),
+            modifier,
+        });
+    }
+    Ok(Expression { operator, vars })
+}
+
+// varname = varchar *( ["."] varchar ), varchar = ALPHA / DIGIT / "_" / pct-encoded
+fn is_varname(s: &str) -> bool {
+    let bs = s.as_bytes();
+    if bs.is_empty() || bs[0] == b'.' || bs[bs.len() - 1] == b'.' {
+        return false;
+    }
+    let mut i = 0;
+    while i < bs.len() {
+        match bs[i] {
+            b'%' => match bs.get(i + 1..i + 3) {
+                Some(h) if h.iter().all(|c| c.is_ascii_hexdigit()) => i += 2,
+                _ => return false,
+            },
+            b'.' if bs[i - 1] == b'.' => return false,
+            c if c.is_ascii_alphanumeric() || c == b'_' || c == b'.' => {}
+            _ => return false,
+        }
+        i += 1;
+    }
+    true
+}
+
+// U 只允许非保留字符, U+R 还允许保留字符与已有的百分比编码
+pub(crate) fn encode_value(s: &str, allow_reserved: bool) -> String {
+    if !allow_reserved {
+        return percent_encode(s, &UNRESERVED);
+    }
+    let bs = s.as_bytes();
+    let allowed = reserved_allowed();
+    let mut buf = String::with_capacity(s.len());
+    let mut i = 0;
+    while i < bs.len() {
+        let c = bs[i];
+        let pct = c == b'%' && bs.get(i + 1..i + 3).is_some_and(|h| h.iter().all(|c| c.is_ascii_hexdigit()));
+        if pct || allowed.contains(c) {
+            buf.push(c as char);
+        } else {
+            buf.push_str(format!("%{:02X}", c).as_str());
+        }
+        i += 1;
+    }
+    buf
+}
+
+fn prefix(s: &str, n: u16) -> &str {
+    match s.char_indices().nth(n as usize) {
+        Some((i, _)) => &s[..i],
+        None => s,
+    }
+}
+
+// rfc6570 附录 A
+fn expand_expression(e: &Expression, vars: &Vars, buf: &mut String) {
+    let op = e.operator;
+    let enc = |s: &str| encode_value(s, op.allow_reserved());
+    let mut first = true;
+    for spec in &e.vars {
+        let value = match vars.get(&spec.name) {
+            Some(v) if v.is_defined() => v,
+            _ => continue,
+        };
+        buf.push_str(if first { op.first() } else { op.sep() });
+        first = false;
+        match (value, spec.modifier) {
+            (Value::String(s), m) => {
+                if op.named() {
+                    buf.push_str(&spec.name);
+                    if s.is_empty() {
+                        buf.push_str(op.ifemp());
+                        continue;
+                    }
+                    buf.push('=');
+                }
+                let s = match m {
+                    Modifier::Prefix(n) => prefix(s, n),
+                    _ => s.as_str(),
+                };
+                buf.push_str(&enc(s));
+            }
+            (Value::List(items), Modifier::Explode) => {
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        buf.push_str(op.sep());
+                    }
+                    if op.named() {
+                        buf.push_str(&spec.name);
+                        if item.is_empty() {
+                            buf.push_str(op.ifemp());
+                            continue;
+                        }
+                        buf.push('=');
+                    }
+                    buf.push_str(&enc(item));
+                }
+            }
+            (Value::Map(pairs), Modifier::Explode) => {
+                for (i, (k, v)) in pairs.iter().enumerate() {
+                    if i > 0 {
+                        buf.push_str(op.sep());
+                    }
+                    buf.push_str(&enc(k));
+                    if op.named() && v.is_empty() {
+                        buf.push_str(op.ifemp());
+                        continue;
+                    }
+                    buf.push('=');
+                    buf.push_str(&enc(v));
+                }
+            }
+            // 前缀修饰对复合值无效
+            (composite, _) => {
+                if op.named() {
+                    buf.push_str(&spec.name);
+                    buf.push('=');
+                }
+                let items: Vec<String> = match composite {
+                    Value::List(items) => items.iter().map(|s| enc(s)).collect(),
+                    Value::Map(pairs) => pairs.iter().flat_map(|(k, v)| [enc(k), enc(v)]).collect(),
+                    Value::String(_) => Vec::new(),
+                };
+                buf.push_str(&items.join(","));
+            }
+        }
+    }
+}
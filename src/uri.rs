@@ -1,16 +1,65 @@
 use crate::Parser;
+use super::authority::{Authority, Host, Port, UserInfo};
+use super::builder::Builder;
 use super::fragment::Fragment;
+use super::path::Path;
 use super::query::Query;
+use super::reference::ReferenceKind;
 use super::rpart::RPart;
 use super::scheme::Scheme;
 use super::error::{Result, Error};
+use super::validate::{ErrorKind, ValidationError};
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt::{Display, Formatter};
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct URI {
-    pub scheme: Option<Scheme>,
-    pub rpart: Option<RPart>,
-    pub query: Option<Query>,
-    pub fragment: Option<Fragment>,
+    scheme: Option<Scheme>,
+    rpart: Option<RPart>,
+    query: Option<Query>,
+    fragment: Option<Fragment>,
+    // 保留模式下解析得到的原文
+    raw: Option<Box<RawParts>>,
+    serialized: String,
+}
+
+// 各部分的原文, 不含分隔符
+#[derive(Debug, Clone)]
+struct RawParts {
+    input: String,
+    scheme: Option<String>,
+    authority: Option<String>,
+    path: String,
+    query: Option<String>,
+    fragment: Option<String>,
+    modified: Modified,
+}
+
+// 保留模式下被修改过的部分
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Modified {
+    pub scheme: bool,
+    pub authority: bool,
+    pub path: bool,
+    pub query: bool,
+    pub fragment: bool,
+}
+
+impl Modified {
+    pub fn any(&self) -> bool {
+        self.scheme || self.authority || self.path || self.query || self.fragment
+    }
+}
+
+type Parts<'a> = (Option<&'a str>, Option<&'a str>, &'a str, Option<&'a str>, Option<&'a str>);
+
+impl Default for URI {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl URI {
@@ -20,96 +69,529 @@ impl URI {
             rpart: None,
             query: None,
             fragment: None,
+            raw: None,
+            serialized: String::new(),
+        }
+    }
+
+    // 调用方需保证各部分已满足 validate 中的约束
+    pub(crate) fn from_parts(scheme: Option<Scheme>, rpart: Option<RPart>, query: Option<Query>, fragment: Option<Fragment>) -> Self {
+        let mut u = URI {
+            scheme,
+            rpart,
+            query,
+            fragment,
+            raw: None,
+            serialized: String::new(),
+        };
+        u.serialize();
+        u
+    }
+
+    pub fn as_str(&self) -> &str {
+        self.serialized.as_str()
+    }
+
+    // 保留模式: 未修改的部分按原文输出, 如小写的百分比编码
+    pub fn parse_preserving(s: &str) -> Result<Self> {
+        let spans = split(s).map_err(|e| Error::Decode(format!("{}, \'{}\'", e, s)))?;
+        let mut u = URI::from_spans(&spans)?;
+        u.raw = Some(Box::new(RawParts {
+            input: s.to_string(),
+            scheme: spans.scheme.map(|v| v.to_string()),
+            authority: spans.authority.map(|v| v.to_string()),
+            path: spans.path.to_string(),
+            query: spans.query.map(|v| v.to_string()),
+            fragment: spans.fragment.map(|v| v.to_string()),
+            modified: Modified::default(),
+        }));
+        u.serialize();
+        Ok(u)
+    }
+
+    pub fn is_preserving(&self) -> bool {
+        self.raw.is_some()
+    }
+
+    // 非保留模式下总是返回全部为 false
+    pub fn is_modified(&self) -> Modified {
+        self.raw.as_ref().map(|r| r.modified).unwrap_or_default()
+    }
+
+    // 保留模式下解析时的原始输入
+    pub fn original(&self) -> Option<&str> {
+        self.raw.as_ref().map(|r| r.input.as_str())
+    }
+
+    // rfc3986 4.3 的 absolute-URI, 拒绝相对引用与 fragment
+    pub fn parse_absolute(s: &str) -> Result<Self> {
+        let u = URI::decode(s)?;
+        if u.scheme.is_none() {
+            return Err(Error::Invalid(format!("expected an absolute uri, got a relative reference \'{}\'", s)));
         }
+        if u.fragment.is_some() {
+            return Err(Error::Invalid(format!("absolute uri cannot contain a fragment, \'{}\'", s)));
+        }
+        Ok(u)
     }
 
-    fn step_decode(&mut self, step: u8, buf: &mut Vec<u8>) -> Result<&mut Self> {
-        let s = String::from_utf8(buf.to_vec())?;
-        let s = s.as_str();
-        match step {
-            0 => {
-                let scheme = Scheme::decode(s)?;
-                self.scheme = Some(scheme)
+    pub fn reference_kind(&self) -> ReferenceKind {
+        if self.scheme.is_some() {
+            return ReferenceKind::AbsoluteUri;
+        }
+        if self.authority().is_some() {
+            return ReferenceKind::NetworkPath;
+        }
+        match self.path() {
+            Some(p) if p.is_absolute() => ReferenceKind::AbsolutePath,
+            Some(p) if !p.is_empty() => ReferenceKind::RelativePath,
+            _ if self.query.is_some() => ReferenceKind::RelativePath,
+            _ => ReferenceKind::SameDocument,
+        }
+    }
+
+    pub fn is_absolute(&self) -> bool {
+        self.scheme.is_some()
+    }
+
+    pub fn builder() -> Builder {
+        Builder::new()
+    }
+
+    pub fn scheme(&self) -> Option<&Scheme> {
+        self.scheme.as_ref()
+    }
+
+    pub fn rpart(&self) -> Option<&RPart> {
+        self.rpart.as_ref()
+    }
+
+    pub fn authority(&self) -> Option<&Authority> {
+        self.rpart.as_ref().and_then(|r| r.authority())
+    }
+
+    pub fn user_info(&self) -> Option<&UserInfo> {
+        self.authority().and_then(|a| a.user_info())
+    }
+
+    pub fn host(&self) -> Option<&Host> {
+        self.authority().map(|a| a.host())
+    }
+
+    pub fn port(&self) -> Option<&Port> {
+        self.authority().and_then(|a| a.port())
+    }
+
+    pub fn path(&self) -> Option<&Path> {
+        self.rpart.as_ref().and_then(|r| r.path())
+    }
+
+    pub fn query(&self) -> Option<&Query> {
+        self.query.as_ref()
+    }
+
+    pub fn fragment(&self) -> Option<&Fragment> {
+        self.fragment.as_ref()
+    }
+
+    pub fn set_scheme(&mut self, s: &str) -> Result<&mut Self> {
+        let scheme = Scheme::decode(s)?;
+        self.update(|u| u.scheme = Some(scheme))
+    }
+
+    pub fn clear_scheme(&mut self) -> Result<&mut Self> {
+        self.update(|u| u.scheme = None)
+    }
+
+    pub fn set_authority(&mut self, s: &str) -> Result<&mut Self> {
+        let auth = Authority::decode(s)?;
+        self.update(|u| {
+            u.rpart.get_or_insert_with(RPart::new).set_authority(Some(auth));
+        })
+    }
+
+    pub fn clear_authority(&mut self) -> Result<&mut Self> {
+        self.update(|u| {
+            if let Some(r) = u.rpart.as_mut() {
+                r.set_authority(None);
             }
-            1 => {
-                let rpart = RPart::decode(s)?;
-                self.rpart = Some(rpart)
+        })
+    }
+
+    // 没有 authority 时自动创建
+    pub fn set_host(&mut self, s: &str) -> Result<&mut Self> {
+        let host = Host::decode(s)?;
+        self.update(|u| {
+            let r = u.rpart.get_or_insert_with(RPart::new);
+            if r.authority().is_some() {
+                r.update_authority(|a| {
+                    a.set_host(host);
+                });
+            } else {
+                r.set_authority(Some(Authority::new(host)));
+            }
+        })
+    }
+
+    pub fn set_port(&mut self, port: u16) -> Result<&mut Self> {
+        self.update_authority(|a| {
+            a.set_port(Port::new(port));
+        })
+    }
+
+    pub fn set_empty_port(&mut self) -> Result<&mut Self> {
+        self.update_authority(|a| {
+            a.set_empty_port();
+        })
+    }
+
+    pub fn clear_port(&mut self) -> Result<&mut Self> {
+        self.update_authority(|a| {
+            a.clear_port();
+        })
+    }
+
+    pub fn set_userinfo(&mut self, s: &str) -> Result<&mut Self> {
+        let info = UserInfo::decode(s)?;
+        self.update_authority(|a| {
+            a.set_userinfo(info);
+        })
+    }
+
+    pub fn clear_userinfo(&mut self) -> Result<&mut Self> {
+        self.update_authority(|a| {
+            a.clear_userinfo();
+        })
+    }
+
+    pub fn set_path(&mut self, s: &str) -> Result<&mut Self> {
+        let path = if s.is_empty() { None } else { Some(Path::decode(s)?) };
+        self.update(|u| {
+            u.rpart.get_or_insert_with(RPart::new).set_path(path);
+        })
+    }
+
+    pub fn set_query(&mut self, s: &str) -> Result<&mut Self> {
+        let query = Query::decode(s)?;
+        self.update(|u| u.query = Some(query))
+    }
+
+    // 替换该键已有的值, 参数为未编码的原文
+    pub fn set_query_pair(&mut self, key: &str, val: &str) -> Result<&mut Self> {
+        self.update(|u| {
+            u.query.get_or_insert_with(Query::new).replace(key, val);
+        })
+    }
+
+    pub fn append_query_pair(&mut self, key: &str, val: &str) -> Result<&mut Self> {
+        self.update(|u| {
+            u.query.get_or_insert_with(Query::new).set(key.to_string(), val);
+        })
+    }
+
+    pub fn remove_query_key(&mut self, key: &str) -> Result<&mut Self> {
+        self.update(|u| {
+            if let Some(q) = u.query.as_mut() {
+                q.remove(key);
             }
-            2 => {
-                let query = Query::decode(s)?;
-                self.query = Some(query)
+        })
+    }
+
+    pub fn clear_query(&mut self) -> Result<&mut Self> {
+        self.update(|u| u.query = None)
+    }
+
+    pub fn set_fragment(&mut self, s: &str) -> Result<&mut Self> {
+        let frag = Fragment::decode(s)?;
+        self.update(|u| u.fragment = Some(frag))
+    }
+
+    pub fn clear_fragment(&mut self) -> Result<&mut Self> {
+        self.update(|u| u.fragment = None)
+    }
+
+    // 校验各部分之间的约束, rfc3986 3.3
+    pub fn validate(&self) -> Result<()> {
+        let path = self.path().map(|p| p.as_str()).unwrap_or("");
+        if self.authority().is_some() {
+            if !path.is_empty() && !path.starts_with('/') {
+                return Err(Error::Invalid("path must start with '/' when authority is present".to_string()));
             }
-            3 => {
-                let fragm = Fragment::decode(s)?;
-                self.fragment = Some(fragm)
+            return Ok(());
+        }
+        if path.starts_with("//") {
+            return Err(Error::Invalid("path cannot start with '//' when authority is absent".to_string()));
+        }
+        if self.scheme.is_none() && path.split('/').next().is_some_and(|seg| seg.contains(':')) {
+            return Err(Error::Invalid("first path segment cannot contain ':' in a relative reference".to_string()));
+        }
+        Ok(())
+    }
+
+    // 修改副本, 校验通过后再替换
+    fn update<F: FnOnce(&mut URI)>(&mut self, f: F) -> Result<&mut Self> {
+        let mut u = self.clone();
+        f(&mut u);
+        u.validate()?;
+        if u.raw.is_some() {
+            let (old, new) = (self.canonical(), u.canonical());
+            let changed = Modified {
+                scheme: old.0 != new.0,
+                authority: old.1 != new.1,
+                path: old.2 != new.2,
+                query: old.3 != new.3,
+                fragment: old.4 != new.4,
+            };
+            if let Some(r) = u.raw.as_mut() {
+                let m = &mut r.modified;
+                m.scheme |= changed.scheme;
+                m.authority |= changed.authority;
+                m.path |= changed.path;
+                m.query |= changed.query;
+                m.fragment |= changed.fragment;
             }
-            _ => return Err(Error::Decode("unknown decode step".to_string()))
         }
-        buf.clear();
+        u.serialize();
+        *self = u;
         Ok(self)
     }
+
+    fn update_authority<F: FnOnce(&mut Authority)>(&mut self, f: F) -> Result<&mut Self> {
+        if self.authority().is_none() {
+            return Err(Error::Invalid("uri has no authority".to_string()));
+        }
+        self.update(|u| {
+            if let Some(r) = u.rpart.as_mut() {
+                r.update_authority(f);
+            }
+        })
+    }
+
+    fn canonical(&self) -> Parts<'_> {
+        (
+            self.scheme.as_ref().map(|s| s.as_str()),
+            self.authority().map(|a| a.as_str()),
+            self.path().map(|p| p.as_str()).unwrap_or(""),
+            self.query.as_ref().map(|q| q.as_str()),
+            self.fragment.as_ref().map(|f| f.as_str()),
+        )
+    }
+
+    // 保留模式下未修改的部分使用原文
+    fn parts(&self) -> Parts<'_> {
+        let c = self.canonical();
+        match &self.raw {
+            None => c,
+            Some(r) => {
+                let m = &r.modified;
+                (
+                    if m.scheme { c.0 } else { r.scheme.as_deref() },
+                    if m.authority { c.1 } else { r.authority.as_deref() },
+                    if m.path { c.2 } else { r.path.as_str() },
+                    if m.query { c.3 } else { r.query.as_deref() },
+                    if m.fragment { c.4 } else { r.fragment.as_deref() },
+                )
+            }
+        }
+    }
+
+    fn serialize(&mut self) {
+        let (scheme, authority, path, query, fragment) = self.parts();
+        // 分隔符各占一个字节, authority 前为 "//"
+        let len = scheme.map_or(0, |s| s.len() + 1)
+            + authority.map_or(0, |a| a.len() + 2)
+            + path.len()
+            + query.map_or(0, |q| q.len() + 1)
+            + fragment.map_or(0, |f| f.len() + 1);
+        let mut buf = String::with_capacity(len);
+        if let Some(sch) = scheme {
+            buf.push_str(sch);
+            buf.push(':');
+        };
+        if let Some(auth) = authority {
+            buf.push_str("//");
+            buf.push_str(auth);
+        }
+        buf.push_str(path);
+        if let Some(query) = query {
+            buf.push('?');
+            buf.push_str(query)
+        }
+        if let Some(frag) = fragment {
+            buf.push('#');
+            buf.push_str(frag)
+        }
+        self.serialized = buf;
+    }
+
+    fn from_spans(spans: &Spans<'_>) -> Result<Self> {
+        let mut r = URI::new();
+        if let Some(sch) = spans.scheme {
+            r.scheme = Some(Scheme::decode(sch)?);
+        }
+        let mut rpart = RPart::new();
+        if let Some(auth) = spans.authority {
+            rpart.set_authority(Some(Authority::decode(auth)?));
+        }
+        if !spans.path.is_empty() {
+            rpart.set_path(Some(Path::decode(spans.path)?));
+        }
+        if spans.authority.is_some() || !spans.path.is_empty() {
+            r.rpart = Some(rpart);
+        }
+        if let Some(q) = spans.query {
+            r.query = Some(Query::decode(q)?);
+        }
+        if let Some(f) = spans.fragment {
+            r.fragment = Some(Fragment::decode(f)?);
+        }
+        r.validate()?;
+        r.serialize();
+        Ok(r)
+    }
+
+    // 用于日志输出, 密码及指定查询参数的值会被掩码替换
+    pub fn redacted(&self) -> Redacted<'_> {
+        Redacted {
+            uri: self,
+            query_keys: Vec::new(),
+        }
+    }
 }
 
 impl Parser for URI {
+    // URI-reference = URI / relative-ref, rfc3986 4.1
     fn decode(s: &str) -> crate::Result<Self> {
-        let mut r = URI::new();
-        let mut s_iter = s.as_bytes().iter();
-        let mut buf = Vec::<u8>::new();
-        let mut step = 0u8; // 0 = scheme, 1 = rpart, 2 = query, 3 = fragment
-        loop {
-            let iter_r = s_iter.next();
-            match (iter_r, step) {
-                (Some(b':'), 0) => {
-                    r.step_decode(step, &mut buf);
-                    step = 1
-                }
-                (Some(b'/'), 0) => {
-                    buf.push(b'/');
-                    step = 1
-                }
-                (Some(b'?'), 0 | 1) => {
-                    r.step_decode(1, &mut buf);
-                    step = 2
-                }
-                (Some(b'#'), 0..=2) => {
-                    r.step_decode(step, &mut buf);
-                    step = 3
-                }
-                (None, _) => {
-                    if buf.len() > 0 {
-                        if step == 0 {
-                            step = 1;
-                        }
-                        r.step_decode(step, &mut buf)?;
-                    }
-                    break;
+        let spans = split(s).map_err(|e| Error::Decode(format!("{}, \'{}\'", e, s)))?;
+        URI::from_spans(&spans)
+    }
+
+    fn encode(&self) -> Result<String> {
+        Ok(self.serialized.clone())
+    }
+}
+
+impl_str_conv!(URI);
+
+#[derive(Clone, Copy, PartialEq)]
+enum State {
+    Scheme,
+    Authority,
+    Path,
+    Query,
+    Fragment,
+}
+
+// 各部分在输入中的切片, 不含分隔符
+#[derive(Default)]
+pub(crate) struct Spans<'a> {
+    pub(crate) scheme: Option<&'a str>,
+    pub(crate) authority: Option<&'a str>,
+    pub(crate) path: &'a str,
+    pub(crate) query: Option<&'a str>,
+    pub(crate) fragment: Option<&'a str>,
+}
+
+// 单次扫描切分各部分, 只识别分隔符, 字符的合法性由各部分自行校验
+pub(crate) fn split(s: &str) -> core::result::Result<Spans<'_>, ValidationError> {
+    let bs = s.as_bytes();
+    let mut spans = Spans::default();
+    let mut state = State::Scheme;
+    let mut start = 0;
+    let mut i = 0;
+    loop {
+        let c = bs.get(i).copied();
+        match (state, c) {
+            // 第一个 ':' 出现在 '/', '?', '#' 之前时才是 scheme
+            (State::Scheme, Some(b':')) => {
+                spans.scheme = Some(&s[..i]);
+                start = i + 1;
+                if s[start..].starts_with("//") {
+                    start += 2;
+                    state = State::Authority;
+                } else {
+                    state = State::Path;
                 }
-                (Some(item), _) => {
-                    buf.push(*item)
+                i = start;
+                continue;
+            }
+            // 没有 scheme, 已扫描的部分属于 relative-part, 当前字符重新处理
+            (State::Scheme, None | Some(b'/' | b'?' | b'#')) => {
+                if s.starts_with("//") {
+                    start = 2;
+                    i = 2;
+                    state = State::Authority;
+                } else {
+                    state = State::Path;
                 }
+                continue;
+            }
+            (State::Authority, None | Some(b'/' | b'?' | b'#')) => {
+                spans.authority = Some(&s[start..i]);
+                start = i;
+                state = State::Path;
+                continue;
+            }
+            (State::Path, None) => {
+                spans.path = &s[start..];
+                break;
+            }
+            (State::Path, Some(b'?' | b'#')) => {
+                spans.path = &s[start..i];
+                start = i + 1;
+                state = if c == Some(b'?') { State::Query } else { State::Fragment };
             }
+            (State::Query, None) => {
+                spans.query = Some(&s[start..]);
+                break;
+            }
+            (State::Query, Some(b'#')) => {
+                spans.query = Some(&s[start..i]);
+                start = i + 1;
+                state = State::Fragment;
+            }
+            (State::Fragment, None) => {
+                spans.fragment = Some(&s[start..]);
+                break;
+            }
+            (State::Fragment, Some(b'#')) => {
+                return Err(ValidationError::new(i, ErrorKind::UnexpectedHash));
+            }
+            _ => {}
         }
-        Ok(r)
+        i += 1;
     }
+    Ok(spans)
+}
 
-    fn encode(&self) -> Result<String> {
-        let mut buf = String::new();
-        if let Some(sch) = &self.scheme {
-            let r = sch.encode()?;
-            buf.push_str(format!("{}:", r).as_str())
-        };
-        if let Some(rpt) = &self.rpart {
-            let r = rpt.encode()?;
-            buf.push_str(r.as_str())
-        }
-        if let Some(query) = &self.query {
-            let r = query.encode()?;
-            buf.push_str(format!("?{}", r).as_str())
-        }
-        if let Some(frag) = &self.fragment {
-            let r = frag.encode()?;
-            buf.push_str(format!("#{}", r).as_str());
-        }
-        Ok(buf)
+pub struct Redacted<'a> {
+    uri: &'a URI,
+    query_keys: Vec<String>,
+}
+
+impl<'a> Redacted<'a> {
+    pub fn query_key(mut self, key: &str) -> Self {
+        self.query_keys.push(key.to_string());
+        self
+    }
+
+    pub fn query_keys(mut self, keys: &[&str]) -> Self {
+        self.query_keys.extend(keys.iter().map(|k| k.to_string()));
+        self
+    }
+
+    pub fn to_uri(&self) -> URI {
+        URI::from_parts(
+            self.uri.scheme.clone(),
+            self.uri.rpart.as_ref().map(|r| r.redacted()),
+            self.uri.query.as_ref().map(|q| q.redacted(&self.query_keys)),
+            self.uri.fragment.clone(),
+        )
+    }
+}
+
+impl<'a> Display for Redacted<'a> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.to_uri().as_str())
     }
 }
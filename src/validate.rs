@@ -0,0 +1,232 @@
+use core::fmt::{Display, Formatter};
+use core::net::Ipv6Addr;
+use core::str::FromStr;
+use alloc::string::ToString;
+use super::authority::{is_ipv_future, Authority, Host, Port, UserInfo};
+use super::encoder::{Allowed, ALPHANUM, PCHAR, SUB_DELIMS, UNRESERVED};
+use super::error::Error;
+use super::fragment::Fragment;
+use super::path::Path;
+use super::query::Query;
+use super::scheme::Scheme;
+use super::uri::{split, URI};
+
+// 只做校验, 不构造任何组件, 也不分配内存
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorKind {
+    // 不允许出现的字符
+    InvalidChar(u8),
+    // '%' 后不是两位十六进制
+    InvalidPercent,
+    InvalidScheme,
+    InvalidHost,
+    InvalidPort,
+    // fragment 中再次出现 '#'
+    UnexpectedHash,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ValidationError {
+    position: usize,
+    kind: ErrorKind,
+}
+
+pub type Result = core::result::Result<(), ValidationError>;
+
+impl ValidationError {
+    pub fn new(position: usize, kind: ErrorKind) -> Self {
+        ValidationError { position, kind }
+    }
+
+    // 出错位置, 为输入中的字节偏移
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    pub fn kind(&self) -> ErrorKind {
+        self.kind
+    }
+
+    fn offset(self, n: usize) -> Self {
+        ValidationError::new(self.position + n, self.kind)
+    }
+}
+
+impl Display for ValidationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self.kind {
+            ErrorKind::InvalidChar(c) if c.is_ascii() => write!(f, "invalid character '{}' at {}", c as char, self.position),
+            ErrorKind::InvalidChar(c) => write!(f, "invalid byte 0x{:02X} at {}", c, self.position),
+            ErrorKind::InvalidPercent => write!(f, "invalid percent encoding at {}", self.position),
+            ErrorKind::InvalidScheme => write!(f, "invalid scheme at {}", self.position),
+            ErrorKind::InvalidHost => write!(f, "invalid host at {}", self.position),
+            ErrorKind::InvalidPort => write!(f, "invalid port at {}", self.position),
+            ErrorKind::UnexpectedHash => write!(f, "unexpected '#' at {} in fragment", self.position),
+        }
+    }
+}
+
+impl From<ValidationError> for Error {
+    fn from(value: ValidationError) -> Self {
+        Error::Decode(value.to_string())
+    }
+}
+
+// 与 encoder::normalize 接受的输入一致
+fn chars(s: &str, allowed: Allowed) -> Result {
+    let bs = s.as_bytes();
+    let mut i = 0;
+    while i < bs.len() {
+        let c = bs[i];
+        if c == b'%' {
+            match bs.get(i + 1..i + 3) {
+                Some(h) if h.iter().all(|c| c.is_ascii_hexdigit()) => i += 3,
+                _ => return Err(ValidationError::new(i, ErrorKind::InvalidPercent)),
+            }
+            continue;
+        }
+        if !allowed.contains(c) {
+            return Err(ValidationError::new(i, ErrorKind::InvalidChar(c)));
+        }
+        i += 1;
+    }
+    Ok(())
+}
+
+// 子串在原串中的偏移
+fn offset_of(s: &str, sub: &str) -> usize {
+    sub.as_ptr() as usize - s.as_ptr() as usize
+}
+
+impl Scheme {
+    pub fn validate(s: &str) -> Result {
+        if !s.as_bytes().first().is_some_and(|c| c.is_ascii_alphabetic()) {
+            return Err(ValidationError::new(0, ErrorKind::InvalidScheme));
+        }
+        match s.bytes().position(|c| !ALPHANUM.with(b"+-.").contains(c)) {
+            Some(i) => Err(ValidationError::new(i, ErrorKind::InvalidScheme)),
+            None => Ok(()),
+        }
+    }
+}
+
+impl UserInfo {
+    pub fn validate(s: &str) -> Result {
+        chars(s, UNRESERVED.union(SUB_DELIMS).with(b":"))
+    }
+}
+
+impl Host {
+    pub fn validate(s: &str) -> Result {
+        if let Some(rest) = s.strip_prefix('[') {
+            let inner = match rest.strip_suffix(']') {
+                Some(inner) => inner,
+                None => return Err(ValidationError::new(s.len(), ErrorKind::InvalidHost)),
+            };
+            if Ipv6Addr::from_str(inner).is_ok() || is_ipv_future(inner) {
+                return Ok(());
+            }
+            return Err(ValidationError::new(1, ErrorKind::InvalidHost));
+        }
+        // IPv4 是 reg-name 的子集
+        chars(s, UNRESERVED.union(SUB_DELIMS))
+    }
+}
+
+impl Port {
+    pub fn validate(s: &str) -> Result {
+        if s.is_empty() {
+            return Err(ValidationError::new(0, ErrorKind::InvalidPort));
+        }
+        let mut port: u16 = 0;
+        for (i, c) in s.bytes().enumerate() {
+            if !c.is_ascii_digit() {
+                return Err(ValidationError::new(i, ErrorKind::InvalidPort));
+            }
+            port = match port.checked_mul(10).and_then(|p| p.checked_add((c - b'0') as u16)) {
+                Some(p) => p,
+                None => return Err(ValidationError::new(i, ErrorKind::InvalidPort)),
+            };
+        }
+        Ok(())
+    }
+}
+
+impl Authority {
+    // 与 Authority::decode 的切分方式一致
+    pub fn validate(s: &str) -> Result {
+        let rest = match s.split_once('@') {
+            Some((u, rest)) => {
+                UserInfo::validate(u)?;
+                rest
+            }
+            None => s,
+        };
+        let (host, port) = if rest.starts_with('[') {
+            let end = match rest.find(']') {
+                Some(i) => i + 1,
+                None => return Err(ValidationError::new(s.len(), ErrorKind::InvalidHost)),
+            };
+            match &rest[end..] {
+                "" => (&rest[..end], None),
+                p if p.starts_with(':') => (&rest[..end], Some(&p[1..])),
+                p => return Err(ValidationError::new(offset_of(s, p), ErrorKind::InvalidHost)),
+            }
+        } else {
+            match rest.split_once(':') {
+                Some((h, p)) => (h, Some(p)),
+                None => (rest, None),
+            }
+        };
+        Host::validate(host).map_err(|e| e.offset(offset_of(s, host)))?;
+        match port {
+            Some(p) if !p.is_empty() => Port::validate(p).map_err(|e| e.offset(offset_of(s, p))),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl Path {
+    pub fn validate(s: &str) -> Result {
+        chars(s, PCHAR.with(b"/"))
+    }
+}
+
+impl Query {
+    pub fn validate(s: &str) -> Result {
+        chars(s, PCHAR.with(b"/?"))
+    }
+}
+
+impl Fragment {
+    pub fn validate(s: &str) -> Result {
+        chars(s, PCHAR.with(b"/?"))
+    }
+}
+
+impl URI {
+    pub fn is_valid(s: &str) -> bool {
+        URI::validate_str(s).is_ok()
+    }
+
+    // 校验 URI-reference, 接受的输入与 URI::decode 一致
+    // 实例方法 validate 用于校验已解析的 URI, 这里换用 validate_str
+    pub fn validate_str(s: &str) -> Result {
+        let spans = split(s)?;
+        if let Some(sch) = spans.scheme {
+            Scheme::validate(sch)?;
+        }
+        if let Some(auth) = spans.authority {
+            Authority::validate(auth).map_err(|e| e.offset(offset_of(s, auth)))?;
+        }
+        Path::validate(spans.path).map_err(|e| e.offset(offset_of(s, spans.path)))?;
+        if let Some(q) = spans.query {
+            Query::validate(q).map_err(|e| e.offset(offset_of(s, q)))?;
+        }
+        if let Some(f) = spans.fragment {
+            Fragment::validate(f).map_err(|e| e.offset(offset_of(s, f)))?;
+        }
+        Ok(())
+    }
+}
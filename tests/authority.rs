@@ -1,10 +1,10 @@
 use std::net::Ipv4Addr;
-use uri::authority::{Authority, Host, Port, UserInfo};
+use uri::authority::{Authority, Host, HostKind, Port, UserInfo};
 use uri::Parser;
 
 #[test]
 fn authority_encode() {
-    let mut auth = Authority::new(Host::RegName(String::from("www.baidu.com")));
+    let mut auth = Authority::new(Host::reg_name("www.baidu.com"));
     // auth.set_host(Host::decode("www.baidu.com").unwrap());
     auth.set_port(Port::new(443));
     // auth.set_userinfo(UserInfo::new("root:passwd"));
@@ -20,19 +20,19 @@ fn authority_decode() {
 
 #[test]
 fn host_encode() {
-    let host_ipl = Host::IPLiteral(String::from("11ff::3344"));
+    let host_ipl = Host::ip_literal("11ff::3344").unwrap();
     match host_ipl.encode() {
         Ok(r) => println!("ip_literal host = {}", r),
         Err(e) => println!("ip_literal err = {}", e),
     }
 
-    let host_v4addr = Host::IPv4Addr(Ipv4Addr::new(192, 168, 1, 1));
+    let host_v4addr = Host::ipv4(Ipv4Addr::new(192, 168, 1, 1));
     match host_v4addr.encode() {
         Ok(v4addr) => println!("v4addr host = {}", v4addr),
         Err(e) => println!("v4addr err = {}", e),
     }
 
-    let host_regname = Host::RegName(String::from("www.代澎源.com"));
+    let host_regname = Host::reg_name("www.代澎源.com");
     match host_regname.encode() {
         Ok(h) => println!("rename host = {}", h),
         Err(e) => println!("rename host err = {}", e),
@@ -60,6 +60,18 @@ fn host_decode() {
     }
 }
 
+#[test]
+fn string_conversions() {
+    let auth: Authority = "root:pw@[fe03::1]:443".parse().unwrap();
+    assert_eq!(auth.to_string(), "root:pw@[fe03::1]:443");
+    assert_eq!(auth.host().to_string(), "[fe03::1]");
+    assert_eq!(auth.port().unwrap().as_ref(), "443");
+    assert_eq!(String::from(auth.user_info().unwrap().clone()), "root:pw");
+    assert_eq!(Host::try_from("www.%E4%BB%A3.com").unwrap().kind(), &HostKind::RegName("www.代.com".to_string()));
+    assert_eq!(Host::reg_name("a b").to_string(), "a%20b");
+    assert!(Port::try_from("70000").is_err());
+}
+
 #[test]
 fn userinfo_encode() {
     let u = UserInfo::new("root:ddd");
@@ -74,6 +86,28 @@ fn userinfo_decode() {
     println!("{:?}", r)
 }
 
+#[test]
+fn userinfo_split() {
+    let u = UserInfo::decode("ro%3Aot:pass:wd%40").unwrap();
+    assert_eq!(u.username(), "ro:ot");
+    assert_eq!(u.password(), Some("pass:wd@"));
+    assert_eq!(u.encode().unwrap(), "ro%3Aot:pass:wd%40");
+
+    let u = UserInfo::decode("root").unwrap();
+    assert_eq!(u.username(), "root");
+    assert_eq!(u.password(), None);
+}
+
+#[test]
+fn userinfo_setter() {
+    let mut u = UserInfo::new("root");
+    u.set_password("passwd");
+    assert_eq!(u.encode().unwrap(), "root:passwd");
+    u.set_username("admin").clear_password();
+    assert_eq!(u.encode().unwrap(), "admin");
+    assert_eq!(UserInfo::with_password("a", "b").redacted().encode().unwrap(), "a:****");
+}
+
 #[test]
 fn port_encode() {
     let u = Port::new(443);
@@ -97,6 +131,7 @@ fn pct_oper() {
     println!("pct_decode = {}", pct_decode(b));
 }
 
+#[allow(clippy::while_let_on_iterator)]
 fn pct_encode(s: &str) -> String {
     let mut buf = String::new();
     let mut iter = s.as_bytes().iter();
@@ -0,0 +1,49 @@
+use uri::authority::{Authority, Host, Port};
+use uri::builder::UriBuilder;
+use uri::fragment::Fragment;
+use uri::path::{PathAbempty, PathAbsolute, PathNoScheme, PathRootless};
+use uri::query::Query;
+use uri::scheme::Scheme;
+use uri::Parser;
+
+#[test]
+fn typestate_full() {
+    let mut auth = Authority::new(Host::reg_name("www.baidu.com"));
+    auth.set_port(Port::new(8080));
+    let mut q = Query::new();
+    q.set("k".into(), "v");
+    let u = UriBuilder::new()
+        .scheme(Scheme::decode("https").unwrap())
+        .authority(auth)
+        .path(PathAbempty::new("/a/b").unwrap())
+        .query(q)
+        .fragment(Fragment::new("top"))
+        .build();
+    assert!(u.validate().is_ok());
+    assert_eq!(u.encode().unwrap(), "https://www.baidu.com:8080/a/b?k=v#top");
+}
+
+#[test]
+fn typestate_without_authority() {
+    let u = UriBuilder::new()
+        .scheme(Scheme::decode("urn").unwrap())
+        .path(PathRootless::new("isbn:0451450523").unwrap())
+        .build();
+    assert_eq!(u.encode().unwrap(), "urn:isbn:0451450523");
+
+    let u = UriBuilder::new().path(PathNoScheme::new("a/b").unwrap()).build();
+    assert_eq!(u.encode().unwrap(), "a/b");
+
+    let u = UriBuilder::new().path(PathAbsolute::new("/a").unwrap()).fragment(Fragment::new("f")).build();
+    assert_eq!(u.encode().unwrap(), "/a#f");
+}
+
+#[test]
+fn typed_paths() {
+    assert!(PathAbsolute::new("//a").is_err());
+    assert!(PathAbempty::new("a").is_err());
+    assert!(PathAbempty::new("").is_ok());
+    assert!(PathRootless::new("/a").is_err());
+    assert!(PathNoScheme::new("a:b/c").is_err());
+    assert!(PathNoScheme::new("a/b:c").is_ok());
+}
@@ -0,0 +1,153 @@
+use uri::reference::ReferenceKind;
+use uri::uri::URI;
+use uri::Parser;
+
+// 已是规范形式的合法 URI-reference, 解析后应原样输出
+const VALID: &[&str] = &[
+    "http://example.com",
+    "http://example.com/",
+    "https://user:pa:ss@example.com:8080/p/a/t/h?query=1&x#frag",
+    "ftp://ftp.is.co.za/rfc/rfc1808.txt",
+    "ldap://[2001:db8::7]/c=GB?objectClass?one",
+    "mailto:John.Doe@example.com",
+    "news:comp.infosystems.www.servers.unix",
+    "tel:+1-816-555-1212",
+    "telnet://192.0.2.16:80/",
+    "urn:oasis:names:specification:docbook:dtd:xml:4.1.2",
+    "foo://info.example.com?fred",
+    "file:///etc/hosts",
+    "http://a/b/c/g;x?y#s",
+    "http://[v1.fe]/",
+    "http://[V1.fe]/",
+    "http://[vAb.x:y~!$]/",
+    "http://h/?",
+    "http://h/#",
+    "http://h?#",
+    "http://@h/",
+    "http://u:@h/",
+    "http://h:/",
+    "http://:80/",
+    "//user@:80",
+    "file://",
+    "s://",
+    "//",
+    "?",
+    "#",
+    "http://h/a%2Fb?k=%26#%23",
+    "http://example.com/%E4%BB%A3",
+    "http://h/%FF",
+    "s://h/a?b/c?d#e/f?g",
+    "a/b:c",
+    "./a:b",
+    "//example.com/path",
+    "/abs/path",
+    "?query",
+    "#frag",
+    "",
+];
+
+// 合法但不是规范形式, 解析后输出规范形式
+const NORMALIZED: &[(&str, &str)] = &[
+    ("http://h/%7e%41", "http://h/~A"),
+    ("http://h/%3a%2f", "http://h/%3A%2F"),
+    ("http://u%7e@h%2d1/?%61=%62#%63", "http://u~@h-1/?a=b#c"),
+    ("http://[2001:DB8:0:0::7]/", "http://[2001:db8::7]/"),
+];
+
+const INVALID: &[&str] = &[
+    "http://[v1.]/",
+    "http://[v.fe]/",
+    "http://[vg.fe]/",
+    "http://[xv1.fe]/",
+    "http://[v1.fe]]/",
+    "http://[v1.f%20e]/",
+    "http://h/ a",
+    "http://h/%zz",
+    "http://h/%4",
+    "1http://h",
+    ":no-scheme",
+    "a b:c",
+    "http://h:port/",
+    "http://h:+80/",
+    "http://[::1/",
+    "http://[::1]x/",
+    "http://[zz]/",
+    "http://h/#a#b",
+    "http://u@v@h/",
+    "http://h/\\",
+    "http://h/<>",
+    "http://h?q=\"",
+    "//h/^",
+    "http://代.com",
+];
+
+// 语法上合法(port = *DIGIT), 但端口以 u16 保存, 超出范围时有意拒绝
+const DEVIATIONS: &[&str] = &[
+    "http://h:65536/",
+    "http://h:99999/",
+];
+
+#[test]
+fn valid_corpus() {
+    for s in VALID {
+        match URI::decode(s) {
+            Ok(u) => assert_eq!(u.to_string(), *s),
+            Err(e) => panic!("{} should be valid, err={}", s, e),
+        }
+        assert!(URI::validate_str(s).is_ok(), "{}", s);
+    }
+}
+
+#[test]
+fn normalized_corpus() {
+    for (s, expected) in NORMALIZED {
+        assert_eq!(URI::decode(s).unwrap().to_string(), *expected, "{}", s);
+        assert!(URI::validate_str(s).is_ok(), "{}", s);
+    }
+}
+
+#[test]
+fn invalid_corpus() {
+    for s in INVALID {
+        assert!(URI::decode(s).is_err(), "{} should be invalid", s);
+        assert!(URI::validate_str(s).is_err(), "{} should be invalid", s);
+    }
+}
+
+#[test]
+fn deviation_corpus() {
+    for s in DEVIATIONS {
+        assert!(URI::decode(s).is_err(), "{} should be rejected", s);
+        assert!(URI::validate_str(s).is_err(), "{} should be rejected", s);
+    }
+    assert!(URI::decode("http://h:65535/").is_ok());
+}
+
+#[test]
+fn delimiters() {
+    let u = URI::decode("a/b:c").unwrap();
+    assert!(u.scheme().is_none());
+    assert_eq!(u.path().unwrap().as_str(), "a/b:c");
+    assert_eq!(u.reference_kind(), ReferenceKind::RelativePath);
+
+    let u = URI::decode("g#s/../x").unwrap();
+    assert!(u.scheme().is_none());
+    assert_eq!(u.fragment().unwrap().as_str(), "s/../x");
+
+    let u = URI::decode("s://h/a?b/c?d#e/f?g").unwrap();
+    assert_eq!(u.path().unwrap().as_str(), "/a");
+    assert_eq!(u.query().unwrap().as_str(), "b/c?d");
+    assert_eq!(u.fragment().unwrap().as_str(), "e/f?g");
+
+    let u = URI::decode("file:///etc/hosts").unwrap();
+    assert_eq!(u.host().unwrap().as_str(), "");
+    assert_eq!(u.path().unwrap().as_str(), "/etc/hosts");
+}
+
+#[test]
+fn component_errors() {
+    let e = URI::decode("http://h/%zz").unwrap_err().to_string();
+    assert!(e.contains("percent"), "{}", e);
+    let e = URI::decode("1http://h").unwrap_err().to_string();
+    assert!(e.contains("scheme"), "{}", e);
+}
@@ -0,0 +1,112 @@
+use uri::csp::{HostSource, SourceExpression, SourceKind, SourceList, SourcePort};
+use uri::origin::Origin;
+use uri::uri::URI;
+use uri::Parser;
+
+fn origin(s: &str) -> Origin {
+    URI::decode(s).unwrap().origin()
+}
+
+fn allows(expr: &str, url: &str) -> bool {
+    SourceExpression::decode(expr).unwrap().matches_str(url, &origin("https://example.com/page")).unwrap()
+}
+
+#[test]
+fn parse() {
+    let kind = |s: &str| SourceExpression::decode(s).unwrap().kind().clone();
+    assert_eq!(kind("'SELF'"), SourceKind::SelfOrigin);
+    assert_eq!(kind("'none'"), SourceKind::None);
+    assert_eq!(kind("'unsafe-inline'"), SourceKind::Keyword("unsafe-inline".to_string()));
+    assert_eq!(kind("'Unsafe-Eval'"), SourceKind::Keyword("unsafe-eval".to_string()));
+    assert_eq!(kind("'NONCE-AbC+/9='"), SourceKind::Keyword("nonce-AbC+/9=".to_string()));
+    assert_eq!(kind("'sha256-Xy/Z='"), SourceKind::Keyword("sha256-Xy/Z=".to_string()));
+    assert_eq!(SourceExpression::decode("'nonce-AbC'").unwrap().to_string(), "'nonce-AbC'");
+    assert_eq!(kind("*"), SourceKind::Wildcard);
+    assert_eq!(kind("HTTPS:"), SourceKind::Scheme("https".to_string()));
+    assert_eq!(
+        kind("*.Example.com:*"),
+        SourceKind::Host(HostSource {
+            scheme: None,
+            host: "*.example.com".to_string(),
+            port: Some(SourcePort::Any),
+            path: None,
+        })
+    );
+    assert_eq!(
+        kind("https://cdn.example:8443/path/"),
+        SourceKind::Host(HostSource {
+            scheme: Some("https".to_string()),
+            host: "cdn.example".to_string(),
+            port: Some(SourcePort::Number(8443)),
+            path: Some("/path/".to_string()),
+        })
+    );
+    for s in ["", "''", "a.*.com", "*x.com", "h:x", "h:8x", "h:99999", "1x://h", "h/a;b", "h/a?b"] {
+        assert!(SourceExpression::decode(s).is_err(), "{}", s);
+    }
+
+    let list = SourceList::decode("  'self'\thttps:  *.cdn.example ").unwrap();
+    assert_eq!(list.len(), 3);
+    assert_eq!(list.to_string(), "'self' https: *.cdn.example");
+}
+
+#[test]
+fn matching() {
+    assert!(allows("*", "https://any.host/x"));
+    assert!(allows("*", "wss://any.host/x"));
+    assert!(!allows("*", "data:text/plain,x"));
+    assert!(allows("data:", "data:text/plain,x"));
+
+    // scheme 升级
+    assert!(allows("http:", "https://a/"));
+    assert!(!allows("https:", "http://a/"));
+    assert!(allows("ws:", "wss://a/"));
+
+    // 没有 scheme 时使用受保护资源的 scheme
+    assert!(allows("cdn.example", "https://CDN.example/lib.js"));
+    assert!(!allows("cdn.example", "http://cdn.example/lib.js"));
+    assert!(!allows("cdn.example", "https://cdn.example:8443/lib.js"));
+    assert!(allows("http://cdn.example", "https://cdn.example/lib.js"));
+    assert!(allows("http://cdn.example:80", "https://cdn.example/lib.js"));
+
+    assert!(allows("*.example.com:*", "https://a.b.example.com:9000/"));
+    assert!(!allows("*.example.com", "https://example.com/"));
+    assert!(!allows("*.example.com", "https://badexample.com/"));
+    assert!(!allows("example.com", "https://127.0.0.1/"));
+    assert!(!allows("127.0.0.1", "https://127.0.0.1/"));
+    assert!(!allows("https://*", "https://[::1]/"));
+    assert!(allows("*", "https://127.0.0.1/"));
+
+    // 路径
+    assert!(allows("https://cdn.example/path/", "https://cdn.example/path/a/b.js"));
+    assert!(!allows("https://cdn.example/path/", "https://cdn.example/pathx/a.js"));
+    assert!(allows("https://cdn.example/a.js", "https://cdn.example/a.js?v=1"));
+    assert!(!allows("https://cdn.example/a.js", "https://cdn.example/a.js/b"));
+    assert!(allows("https://cdn.example/%61b/", "https://cdn.example/ab/c"));
+    assert!(allows("https://cdn.example/", "https://cdn.example"));
+
+    assert!(!allows("'none'", "https://example.com/"));
+    assert!(!allows("'unsafe-inline'", "https://example.com/"));
+}
+
+#[test]
+fn self_origin() {
+    let expr = SourceExpression::decode("'self'").unwrap();
+    let http = origin("http://example.com/");
+    assert!(expr.matches_str("http://example.com:80/a", &http).unwrap());
+    assert!(expr.matches_str("https://example.com/a", &http).unwrap());
+    assert!(expr.matches_str("ws://example.com/a", &http).unwrap());
+    assert!(!expr.matches_str("http://example.com:8080/a", &http).unwrap());
+    assert!(!expr.matches_str("http://www.example.com/a", &http).unwrap());
+
+    let https = origin("https://example.com/");
+    assert!(!expr.matches_str("http://example.com/a", &https).unwrap());
+    assert!(expr.matches_str("wss://example.com/a", &https).unwrap());
+    assert!(!expr.matches_str("https://example.com/", &Origin::Opaque).unwrap());
+
+    let list = SourceList::decode("'self' https://cdn.example/js/").unwrap();
+    assert!(list.matches_str("https://example.com/app.js", &https).unwrap());
+    assert!(list.matches_str("https://cdn.example/js/app.js", &https).unwrap());
+    assert!(!list.matches_str("https://cdn.example/css/app.css", &https).unwrap());
+    assert!(!SourceList::decode("").unwrap().matches_str("https://example.com/", &https).unwrap());
+}
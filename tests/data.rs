@@ -0,0 +1,55 @@
+use uri::data::DataUri;
+use uri::uri::URI;
+use uri::Parser;
+
+#[test]
+fn decode() {
+    let d = DataUri::decode("data:,A%20brief%20note").unwrap();
+    assert_eq!(d.media_type(), "text/plain");
+    assert_eq!(d.charset(), Some("US-ASCII"));
+    assert!(!d.is_base64());
+    assert_eq!(d.data(), b"A brief note");
+
+    let d = DataUri::decode("DATA:Text/HTML;Charset=UTF-8;base64,PGI+aGk8L2I+").unwrap();
+    assert_eq!(d.media_type(), "text/html");
+    assert_eq!(d.charset(), Some("UTF-8"));
+    assert_eq!(d.params().collect::<Vec<_>>(), vec![("charset", "UTF-8")]);
+    assert!(d.is_base64());
+    assert_eq!(d.data(), b"<b>hi</b>");
+    assert_eq!(d.to_string(), "data:text/html;charset=UTF-8;base64,PGI+aGk8L2I+");
+
+    let d = DataUri::decode("data:image/png;base64,iVBORw0KGgo").unwrap();
+    assert_eq!(d.data(), [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1A, b'\n']);
+    assert_eq!(DataUri::decode("data:;base64,").unwrap().data(), b"");
+    assert_eq!(DataUri::decode("data:;base64,YQ%3D%3D").unwrap().data(), b"a");
+    assert_eq!(DataUri::decode("data:,%FF%00").unwrap().data(), [0xFF, 0x00]);
+
+    let u = URI::decode("data:text/plain;name=a%20b,x").unwrap();
+    assert_eq!(DataUri::try_from(&u).unwrap().param("NAME"), Some("a b"));
+
+    for s in ["data:text/plain", "http://a/,b", "data:text,a", "data:;base64,YQ=", "data:;base64,Y", "data:;base64,Y*==", "data:a/b;c,x", "data:,a b"] {
+        assert!(DataUri::decode(s).is_err(), "{}", s);
+    }
+}
+
+#[test]
+fn build() {
+    let mut d = DataUri::new("image/png", &[0x89, b'P', b'N', b'G']).unwrap();
+    assert_eq!(d.to_string(), "data:image/png;base64,iVBORw==");
+    d.set_data(b"ab").set_base64(false);
+    assert_eq!(d.as_str(), "data:image/png,ab");
+    d.set_media_type("text/plain").unwrap().set_param("charset", "utf-8").unwrap();
+    d.set_data("a b/ü?".as_bytes());
+    assert_eq!(d.as_str(), "data:text/plain;charset=utf-8,a%20b/%C3%BC?");
+    d.set_param("Charset", "iso 8859-1").unwrap();
+    assert_eq!(d.as_str(), "data:text/plain;charset=iso%208859-1,a%20b/%C3%BC?");
+    assert!(d.set_media_type("text").is_err());
+    assert!(d.set_param("base64", "x").is_err());
+    assert_eq!(d.remove_param("charset").to_uri().unwrap().as_str(), "data:text/plain,a%20b/%C3%BC?");
+
+    for data in [&b""[..], b"f", b"fo", b"foo", b"foob", b"fooba", b"foobar", &[0, 0xFF, 0x10, 0x80]] {
+        let d = DataUri::new("", data).unwrap();
+        assert_eq!(DataUri::decode(d.as_str()).unwrap().data(), data, "{}", d);
+    }
+    assert_eq!(DataUri::new("", b"foobar").unwrap().as_str(), "data:;base64,Zm9vYmFy");
+}
@@ -0,0 +1,117 @@
+use std::time::{Duration, Instant};
+use uri::template::{Template, Value, Vars};
+use uri::uri::URI;
+use uri::Parser;
+
+fn extract(t: &str, s: &str) -> Option<Vars> {
+    Template::decode(t).unwrap().extract_str(s).unwrap()
+}
+
+fn s(v: &str) -> Value {
+    Value::String(v.to_string())
+}
+
+#[test]
+fn simple_and_reserved() {
+    let vars = extract("/repos/{owner}/{repo}", "/repos/rs%20common/uri").unwrap();
+    assert_eq!(vars["owner"], s("rs common"));
+    assert_eq!(vars["repo"], s("uri"));
+    assert!(extract("/repos/{owner}/{repo}", "/repos/a/b/c").is_none());
+    assert!(extract("/repos/{owner}", "/users/a").is_none());
+
+    let vars = extract("{+path}/here", "/foo/bar/here").unwrap();
+    assert_eq!(vars["path"], s("/foo/bar"));
+    let vars = extract("{list}", "red,green,blue").unwrap();
+    assert_eq!(vars["list"], Value::List(vec!["red".into(), "green".into(), "blue".into()]));
+    let vars = extract("{keys*}", "semi=%3B,dot=.").unwrap();
+    assert_eq!(vars["keys"], Value::Map(vec![("semi".into(), ";".into()), ("dot".into(), ".".into())]));
+}
+
+#[test]
+fn path_and_query() {
+    let vars = extract("{/path*}", "/a/b%2Fc/d").unwrap();
+    assert_eq!(vars["path"], Value::List(vec!["a".into(), "b/c".into(), "d".into()]));
+
+    let t = Template::decode("http://h/search{?q,page}").unwrap();
+    let vars = t.extract(&URI::decode("http://h/search?page=2&q=a%20b").unwrap()).unwrap().unwrap();
+    assert_eq!(vars["q"], s("a b"));
+    assert_eq!(vars["page"], s("2"));
+    let vars = t.extract_str("http://h/search").unwrap().unwrap();
+    assert!(vars.is_empty());
+    assert!(t.extract_str("http://h/search?other=1").unwrap().is_none());
+
+    let vars = extract("/x{?list*}", "/x?list=red&list=green").unwrap();
+    assert_eq!(vars["list"], Value::List(vec!["red".into(), "green".into()]));
+    let vars = extract("/x{?keys*}", "/x?semi=%3B&dot=.").unwrap();
+    assert_eq!(vars["keys"], Value::Map(vec![("semi".into(), ";".into()), ("dot".into(), ".".into())]));
+    let vars = extract("/x?fixed=yes{&x}", "/x?fixed=yes&x=1024").unwrap();
+    assert_eq!(vars["x"], s("1024"));
+}
+
+#[test]
+fn round_trip() {
+    let mut vars = Vars::new();
+    vars.insert("owner".into(), "rs common".into());
+    vars.insert("path".into(), vec!["a", "b/c"].into());
+    vars.insert("page".into(), "2".into());
+    let t = Template::decode("/{owner}{/path*}{?page}").unwrap();
+    assert_eq!(t.extract_str(&t.expand(&vars)).unwrap(), Some(vars));
+}
+
+#[test]
+fn ambiguity() {
+    let t = Template::decode("{a}{b}").unwrap();
+    assert!(t.extract_str("xy").is_err());
+    let t = Template::decode("{a}.{b}").unwrap();
+    assert!(t.extract_str("x.y.z").is_err());
+    assert!(t.extract_str("x.y").is_ok());
+    // 只有一个值时无法确定属于哪个变量
+    let t = Template::decode("{/x,y}").unwrap();
+    assert!(t.extract_str("/1").is_err());
+    assert_eq!(t.extract_str("/1/2").unwrap().unwrap()["y"], s("2"));
+    // 同名变量取值需一致
+    let t = Template::decode("/{a}/{a}").unwrap();
+    assert!(t.extract_str("/1/1").unwrap().is_some());
+    assert!(t.extract_str("/1/2").unwrap().is_none());
+}
+
+#[test]
+fn long_non_matching_input() {
+    // 搜索过的状态不会重复搜索
+    let t = Template::decode("{a}{b}{c}{d}{e}/x").unwrap();
+    let input = "a".repeat(160);
+    assert_eq!(t.extract_str(&input).unwrap(), None);
+    let t = Template::decode("/{a}/{b}{c}{a}/x").unwrap();
+    assert_eq!(t.extract_str(&format!("/{}/{}", "a".repeat(40), "b".repeat(120))).unwrap(), None);
+}
+
+#[test]
+fn adversarial_input() {
+    // 每个用例都应在线性附近的时间内结束
+    let cases = [
+        ("{a}{b}{c}{d}{e}/x", "a".repeat(20000)),
+        ("{a}{b}{c}{d}{e}/x", format!("{}/y", "a/".repeat(5000))),
+        ("{;a}{;b}{;c}", ";a".repeat(5000)),
+        ("{a}{b}{c}{d}{e}", format!("{}!", "a".repeat(20000))),
+        ("{x}{y}{x}{y}", "ab".repeat(2000)),
+        ("{/a*}{/b*}{c:3}/x", "/a".repeat(5000)),
+    ];
+    for (t, input) in cases {
+        let t = Template::decode(t).unwrap();
+        let start = Instant::now();
+        let _ = t.extract_str(&input);
+        assert!(start.elapsed() < Duration::from_secs(2), "{} took {:?}", t, start.elapsed());
+    }
+}
+
+#[test]
+fn prefix() {
+    let t = Template::decode("{x:2}").unwrap();
+    assert_eq!(extract("{x:2}", "ab").unwrap()["x"], s("ab"));
+    assert_eq!(extract("{x:2}", "a").unwrap()["x"], s("a"));
+    assert!(t.extract_str("abcd").unwrap().is_none());
+    assert_eq!(extract("{x:2}.{y}", "ab.cd").unwrap()["y"], s("cd"));
+    assert!(Template::decode("{x:2}{y}").unwrap().extract_str("abcd").is_err());
+    assert!(extract("{?q:3}", "?q=abcd").is_none());
+    assert_eq!(extract("{?q:3}", "?q=%C3%BCbc").unwrap()["q"], s("übc"));
+}
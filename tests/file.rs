@@ -0,0 +1,78 @@
+use uri::file::PathStyle;
+use uri::uri::URI;
+use uri::Parser;
+
+fn unix(s: &str) -> String {
+    URI::decode(s).unwrap().to_path_string(PathStyle::Unix).unwrap()
+}
+
+fn windows(s: &str) -> String {
+    URI::decode(s).unwrap().to_path_string(PathStyle::Windows).unwrap()
+}
+
+#[test]
+fn unix_style() {
+    let u = URI::from_path_str("/home/a b/ü#1?%.txt", PathStyle::Unix).unwrap();
+    assert_eq!(u.as_str(), "file:///home/a%20b/%C3%BC%231%3F%25.txt");
+    assert_eq!(u.to_path_string(PathStyle::Unix).unwrap(), "/home/a b/ü#1?%.txt");
+    assert!(URI::from_path_str("relative/a", PathStyle::Unix).is_err());
+
+    assert_eq!(unix("file:///etc/hosts"), "/etc/hosts");
+    assert_eq!(unix("file://localhost/etc/hosts"), "/etc/hosts");
+    assert_eq!(unix("FILE://LocalHost/etc/hosts?x#y"), "/etc/hosts");
+    assert_eq!(unix("file:/etc/hosts"), "/etc/hosts");
+    assert_eq!(unix("file:///"), "/");
+    assert_eq!(unix("file:///C:/a"), "/C:/a");
+    for s in ["file://server/etc", "http://h/etc", "file:///a%2Fb", "file:///a%00", "file:///%FF"] {
+        assert!(URI::decode(s).unwrap().to_path_string(PathStyle::Unix).is_err(), "{}", s);
+    }
+}
+
+#[test]
+fn windows_style() {
+    let from = |s: &str| URI::from_path_str(s, PathStyle::Windows).unwrap().to_string();
+    assert_eq!(from(r"C:\Program Files\a#b.txt"), "file:///C:/Program%20Files/a%23b.txt");
+    assert_eq!(from("c:/x/y"), "file:///c:/x/y");
+    assert_eq!(from(r"C:\"), "file:///C:/");
+    assert_eq!(from(r"\\server\share\dir\f.txt"), "file://server/share/dir/f.txt");
+    assert_eq!(from(r"\\?\D:\very\long"), "file:///D:/very/long");
+    assert_eq!(from(r"\\?\UNC\server\share\a"), "file://server/share/a");
+    for s in [r"a\b", r"\a\b", "C:", "C:a", r"\\server", r"\\\share", r"\\a#b\share\x", r"\\h:99\share", r"\\a%41\share", r"\\[::1]x\share"] {
+        assert!(URI::from_path_str(s, PathStyle::Windows).is_err(), "{}", s);
+    }
+
+    assert_eq!(windows("file:///C:/Program%20Files/a.txt"), r"C:\Program Files\a.txt");
+    assert_eq!(windows("file://localhost/c%7C/x"), r"c:\x");
+    assert_eq!(windows("file:///C:"), r"C:\");
+    assert_eq!(windows("file://server/share/f.txt"), r"\\server\share\f.txt");
+    for s in [r"\\server\share\dir\f.txt", r"\\10.0.0.1\share\a b", r"\\my-host.example\c$\x"] {
+        let u = URI::from_path_str(s, PathStyle::Windows).unwrap();
+        assert_eq!(u.to_path_string(PathStyle::Windows).unwrap(), s);
+    }
+    for s in ["file:///etc/hosts", "file://server/", "file:///C:/a%5Cb"] {
+        assert!(URI::decode(s).unwrap().to_path_string(PathStyle::Windows).is_err(), "{}", s);
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn native() {
+    use std::path::{Path, PathBuf};
+
+    assert!(URI::from_file_path(Path::new("relative")).is_err());
+    let dir = std::env::temp_dir().join("a b");
+    let u = URI::from_file_path(&dir).unwrap();
+    assert!(u.as_str().starts_with("file:///"));
+    assert_eq!(u.to_file_path().unwrap(), dir);
+
+    #[cfg(unix)]
+    {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+
+        let p = Path::new(OsStr::from_bytes(b"/tmp/\xFF\xFEx"));
+        let u = URI::from_file_path(p).unwrap();
+        assert_eq!(u.as_str(), "file:///tmp/%FF%FEx");
+        assert_eq!(u.to_file_path().unwrap(), PathBuf::from(p));
+    }
+}
@@ -0,0 +1,61 @@
+use uri::mailto::MailtoUri;
+use uri::Parser;
+
+#[test]
+fn decode() {
+    let m = MailtoUri::decode("mailto:a@example.com,b%40x@example.org?to=c@example.net&Cc=d@x.com,%20e@x.com&subject=Hello%20World%26more&body=line1%0D%0Aline2+x&X-Ticket=42").unwrap();
+    assert_eq!(m.to(), ["a@example.com", "b@x@example.org", "c@example.net"]);
+    assert_eq!(m.cc(), ["d@x.com", "e@x.com"]);
+    assert!(m.bcc().is_empty());
+    assert_eq!(m.subject(), Some("Hello World&more"));
+    assert_eq!(m.body(), Some("line1\r\nline2+x"));
+    assert_eq!(m.header("x-ticket"), Some("42"));
+    assert_eq!(m.headers().count(), 4);
+
+    let m = MailtoUri::decode("mailto:a@b?cc=x@y&CC=z@w&bcc=p@q&bcc=r@s&subject=a&subject=b").unwrap();
+    assert_eq!(m.cc(), ["x@y", "z@w"]);
+    assert_eq!(m.bcc(), ["p@q", "r@s"]);
+    assert_eq!(m.to_string(), "mailto:a@b?cc=x@y,z@w&bcc=p@q,r@s&subject=a&subject=b");
+
+    let m = MailtoUri::decode("MAILTO:?subject=hi").unwrap();
+    assert!(m.to().is_empty());
+    assert_eq!(m.to_string(), "mailto:?subject=hi");
+
+    for s in ["http://a@b", "mailto://a@b", "mailto:nobody", "mailto:a@b?to=x", "mailto:a@b?=x", "mailto:a@b?subject=hi%0D%0ABcc:%20evil@x", "mailto:a@b?x-h=%0A"] {
+        assert!(MailtoUri::decode(s).is_err(), "{}", s);
+    }
+}
+
+#[test]
+fn build() {
+    let mut m = MailtoUri::new();
+    assert_eq!(m.as_str(), "mailto:");
+    m.add_to("support@example.com").unwrap().add_to("\"a b\"@example.com").unwrap();
+    m.add_cc("x@example.com").unwrap().add_cc("y@example.com").unwrap().add_bcc("z@example.com").unwrap();
+    m.set_subject("Ticket #1 & 100% done?").unwrap().set_body("Hi,\nline two\rthree\r\n").unwrap();
+    assert_eq!(m.body(), Some("Hi,\r\nline two\r\nthree\r\n"));
+    assert_eq!(
+        m.as_str(),
+        "mailto:support@example.com,%22a%20b%22@example.com?cc=x@example.com,y@example.com&bcc=z@example.com\
+         &subject=Ticket%20%231%20%26%20100%25%20done%3F&body=Hi,%0D%0Aline%20two%0D%0Athree%0D%0A"
+    );
+
+    let back = MailtoUri::decode(m.as_str()).unwrap();
+    assert_eq!(back.to(), m.to());
+    assert_eq!(back.cc(), ["x@example.com", "y@example.com"]);
+    assert_eq!(back.subject(), m.subject());
+    assert_eq!(back.body(), m.body());
+    assert_eq!(m.to_uri().unwrap().query().unwrap().get("bcc"), Some("z@example.com"));
+
+    assert!(m.add_to("nobody").is_err());
+    assert!(m.add_cc("a,b@example.com").is_err());
+    assert!(m.set_header("to", "a@b").is_err());
+    assert!(m.set_header("", "x").is_err());
+    assert!(m.set_subject("hi\r\nBcc: evil@x").is_err());
+    assert!(m.set_header("X-Note", "a\nb").is_err());
+    m.clear_to().remove_header("cc").remove_header("bcc").remove_header("body");
+    assert_eq!(m.as_str(), "mailto:?subject=Ticket%20%231%20%26%20100%25%20done%3F");
+    m.set_header("X-Path", "a/b=c&d").unwrap();
+    assert_eq!(m.as_str(), "mailto:?subject=Ticket%20%231%20%26%20100%25%20done%3F&X-Path=a%2Fb%3Dc%26d");
+    assert_eq!(MailtoUri::decode(m.as_str()).unwrap().header("x-path"), Some("a/b=c&d"));
+}
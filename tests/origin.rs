@@ -0,0 +1,78 @@
+use uri::origin::{Origin, PublicSuffixList};
+use uri::uri::URI;
+use uri::Parser;
+
+fn origin(s: &str) -> Origin {
+    URI::decode(s).unwrap().origin()
+}
+
+#[test]
+fn tuple_origin() {
+    let o = origin("HTTPS://Example.COM/a?b#c");
+    assert_eq!((o.scheme(), o.host(), o.port()), (Some("https"), Some("example.com"), Some(443)));
+    assert_eq!(o.ascii_serialization(), "https://example.com");
+    assert_eq!(origin("http://h:8080/").to_string(), "http://h:8080");
+    assert_eq!(origin("http://h:80/").to_string(), "http://h");
+    assert_eq!(origin("http://A%c3%A4B.com/").host(), Some("a%C3%A4b.com"));
+    assert_eq!(origin("http://[::1]:3000").to_string(), "http://[::1]:3000");
+    assert_eq!(origin("blob:https://a.example/uuid").to_string(), "https://a.example");
+
+    for s in ["mailto:a@b", "file:///etc/hosts", "urn:isbn:1", "data:text/plain,x", "blob:null/x", "/relative", "http:no-host"] {
+        let o = origin(s);
+        assert!(o.is_opaque(), "{}", s);
+        assert_eq!(o.to_string(), "null");
+        assert!(!o.is_same_origin(&o));
+    }
+}
+
+#[test]
+fn same_origin() {
+    let a = URI::decode("https://example.com/a").unwrap();
+    assert!(a.is_same_origin(&URI::decode("https://EXAMPLE.com:443/b?c").unwrap()));
+    assert!(!a.is_same_origin(&URI::decode("http://example.com/a").unwrap()));
+    assert!(!a.is_same_origin(&URI::decode("https://example.com:8443/a").unwrap()));
+    assert!(!a.is_same_origin(&URI::decode("https://www.example.com/a").unwrap()));
+    assert!(!a.is_same_origin(&URI::decode("https://example.com./a").unwrap()));
+    assert_eq!(origin("https://Example.com./").to_string(), "https://example.com.");
+}
+
+#[test]
+fn same_site() {
+    let a = URI::decode("https://www.example.com/").unwrap();
+    assert!(a.is_same_site(&URI::decode("https://api.example.com:8443/").unwrap()));
+    assert!(!a.is_same_site(&URI::decode("http://www.example.com/").unwrap()));
+    assert!(!a.is_same_site(&URI::decode("https://example.org/").unwrap()));
+
+    assert_eq!(origin("https://a.b.example.co.uk").registrable_domain(), Some("example.co.uk"));
+    assert!(!origin("https://a.co.uk").is_same_site(&origin("https://b.co.uk")));
+    assert!(!origin("https://alice.github.io").is_same_site(&origin("https://bob.github.io")));
+    assert_eq!(origin("https://co.uk").registrable_domain(), None);
+    assert_eq!(origin("https://localhost").registrable_domain(), None);
+    assert_eq!(origin("https://127.0.0.1").registrable_domain(), None);
+    assert!(origin("https://127.0.0.1").is_same_site(&origin("https://127.0.0.1:8443")));
+    assert!(!origin("https://127.0.0.1").is_same_site(&origin("https://127.0.0.2")));
+
+    // 未知的后缀没有可注册域名, 只有 host 相同才是 same-site
+    assert_eq!(origin("https://a.co.il").registrable_domain(), None);
+    assert!(!origin("https://a.co.il").is_same_site(&origin("https://b.co.il")));
+    assert!(origin("https://a.co.il").is_same_site(&origin("https://a.co.il:8443")));
+    assert!(!origin("https://a.s3.amazonaws.com").is_same_site(&origin("https://b.s3.amazonaws.com")));
+}
+
+struct Il;
+
+impl PublicSuffixList for Il {
+    fn public_suffix<'a>(&self, host: &'a str) -> Option<&'a str> {
+        host.strip_suffix(".co.il").map(|h| &host[h.len() + 1..])
+    }
+}
+
+#[test]
+fn custom_suffixes() {
+    let (a, b) = (origin("https://a.shop.co.il"), origin("https://b.shop.co.il"));
+    assert!(!a.is_same_site(&b));
+    assert!(a.is_same_site_with(&b, &Il));
+    assert_eq!(a.registrable_domain_with(&Il), Some("shop.co.il"));
+    assert!(!origin("https://a.co.il").is_same_site_with(&origin("https://b.co.il"), &Il));
+    assert_eq!(origin("https://a.example.com").registrable_domain_with(&Il), None);
+}
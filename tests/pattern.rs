@@ -0,0 +1,61 @@
+use uri::pattern::{HostPattern, UriPattern, UriPatternSet};
+use uri::uri::URI;
+use uri::Parser;
+
+fn is_match(p: &str, u: &str) -> bool {
+    UriPattern::decode(p).unwrap().matches_str(u).unwrap()
+}
+
+#[test]
+fn single() {
+    let p = "https://*.example.com/api/*";
+    assert!(is_match(p, "https://example.com/api/v1"));
+    assert!(is_match(p, "https://a.b.EXAMPLE.com/api/"));
+    assert!(is_match(p, "https://a.example.com:8443/api/x?y=1"));
+    assert!(!is_match(p, "https://badexample.com/api/v1"));
+    assert!(!is_match(p, "http://example.com/api/v1"));
+    assert!(!is_match(p, "https://example.com/apix"));
+
+    let p = "*://*/*.pdf";
+    assert!(is_match(p, "http://h/a/b.pdf"));
+    assert!(is_match(p, "ftp://h/b.pdf"));
+    assert!(!is_match(p, "http://h/b.pdf?download=1"));
+    assert!(!is_match(p, "/b.pdf"));
+
+    assert!(is_match("http://h:80/*", "http://h/"));
+    assert!(is_match("http://h:80/*", "http://h:80/a"));
+    assert!(!is_match("http://h:80/*", "http://h:8080/a"));
+    assert!(is_match("http://h:*/*", "http://h:8080/a"));
+    assert!(is_match("http://[::1]:8080/*", "http://[::1]:8080/"));
+    assert!(is_match("file:///etc/*", "file:///etc/hosts"));
+    assert!(is_match("http://h/", "http://h"));
+    assert!(is_match("<all_urls>", "mailto:a@b"));
+    assert!(!is_match("<all_urls>", "//h/a"));
+    assert!(is_match("http://h/a*b*c", "http://h/aXbYbZc"));
+    assert!(!is_match("http://h/a*b*c", "http://h/aXbYcZ"));
+
+    let p = UriPattern::decode("*://*.Example.com/*").unwrap();
+    assert_eq!(p.host(), &HostPattern::Subdomains("example.com".to_string()));
+    assert_eq!(p.to_string(), "*://*.Example.com/*");
+    for s in ["http://h", "h/*", "1x://h/*", "http://a.*.com/*", "http://*x.com/*", "http:///*", "http://h:99999/*"] {
+        assert!(UriPattern::decode(s).is_err(), "{}", s);
+    }
+}
+
+#[test]
+fn set() {
+    let mut set = UriPatternSet::new();
+    set.add_str("https://*.example.com/api/*").unwrap();
+    set.add_str("*://*/*.pdf").unwrap();
+    set.add_str("http://static.example.com/*").unwrap();
+    set.add_str("https://other.org/*").unwrap();
+    assert_eq!(set.len(), 4);
+
+    let u = URI::decode("https://cdn.example.com/api/doc.pdf").unwrap();
+    assert_eq!(set.matches(&u), vec![0, 1]);
+    let u = URI::decode("http://static.example.com/a.pdf").unwrap();
+    assert_eq!(set.matches(&u), vec![1, 2]);
+    assert!(set.is_match(&URI::decode("https://other.org/").unwrap()));
+    assert!(!set.is_match(&URI::decode("https://example.org/").unwrap()));
+    assert_eq!(set.get(3).unwrap().as_str(), "https://other.org/*");
+}
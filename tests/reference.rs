@@ -0,0 +1,37 @@
+use uri::reference::{ReferenceKind, UriReference};
+use uri::uri::URI;
+use uri::Parser;
+
+#[test]
+fn classify() {
+    let cases = [
+        ("http://a/b/c?q#f", ReferenceKind::AbsoluteUri),
+        ("urn:isbn:0451450523", ReferenceKind::AbsoluteUri),
+        ("//a/b", ReferenceKind::NetworkPath),
+        ("/a/b", ReferenceKind::AbsolutePath),
+        ("a/b", ReferenceKind::RelativePath),
+        ("../a", ReferenceKind::RelativePath),
+        ("?q=1", ReferenceKind::RelativePath),
+        ("#frag", ReferenceKind::SameDocument),
+        ("", ReferenceKind::SameDocument),
+    ];
+    for (s, kind) in cases {
+        assert_eq!(UriReference::parse(s).unwrap().kind(), kind, "{}", s);
+        assert_eq!(URI::decode(s).unwrap().reference_kind(), kind, "{}", s);
+    }
+}
+
+#[test]
+fn strict_entry_points() {
+    assert!(URI::parse_absolute("http://a/b").is_ok());
+    assert!(URI::parse_absolute("//a/b").is_err());
+    assert!(URI::parse_absolute("/a/b").is_err());
+    assert!(URI::parse_absolute("http://h/#frag").is_err());
+    assert!(URI::parse_absolute("http://h/#").is_err());
+
+    assert!(UriReference::parse_relative("//a/b").is_ok());
+    assert!(UriReference::parse_relative("http://a/b").is_err());
+    let r: UriReference = "/a?x=1".parse().unwrap();
+    assert!(r.is_relative());
+    assert_eq!(r.to_string(), "/a?x=1");
+}
@@ -0,0 +1,88 @@
+use uri::uri::URI;
+use uri::Parser;
+
+const BASE: &str = "http://a/b/c/d;p?q";
+
+#[test]
+fn resolve_normal_examples() {
+    // rfc3986 5.4.1
+    let cases = [
+        ("g:h", "g:h"),
+        ("g", "http://a/b/c/g"),
+        ("./g", "http://a/b/c/g"),
+        ("g/", "http://a/b/c/g/"),
+        ("/g", "http://a/g"),
+        ("//g", "http://g"),
+        ("?y", "http://a/b/c/d;p?y"),
+        ("g?y", "http://a/b/c/g?y"),
+        ("#s", "http://a/b/c/d;p?q#s"),
+        ("g#s", "http://a/b/c/g#s"),
+        ("g?y#s", "http://a/b/c/g?y#s"),
+        (";x", "http://a/b/c/;x"),
+        ("g;x", "http://a/b/c/g;x"),
+        ("g;x?y#s", "http://a/b/c/g;x?y#s"),
+        ("", "http://a/b/c/d;p?q"),
+        (".", "http://a/b/c/"),
+        ("./", "http://a/b/c/"),
+        ("..", "http://a/b/"),
+        ("../", "http://a/b/"),
+        ("../g", "http://a/b/g"),
+        ("../..", "http://a/"),
+        ("../../", "http://a/"),
+        ("../../g", "http://a/g"),
+    ];
+    let base = URI::decode(BASE).unwrap();
+    for (r, expected) in cases {
+        let u = base.resolve(&URI::decode(r).unwrap()).unwrap();
+        assert_eq!(u.to_string(), expected, "{}", r);
+    }
+}
+
+#[test]
+fn resolve_abnormal_examples() {
+    // rfc3986 5.4.2
+    let cases = [
+        ("../../../g", "http://a/g"),
+        ("/./g", "http://a/g"),
+        ("/../g", "http://a/g"),
+        ("g.", "http://a/b/c/g."),
+        ("..g", "http://a/b/c/..g"),
+        ("./../g", "http://a/b/g"),
+        ("g/./h", "http://a/b/c/g/h"),
+        ("g/../h", "http://a/b/c/h"),
+        ("g;x=1/./y", "http://a/b/c/g;x=1/y"),
+        ("g?y/./x", "http://a/b/c/g?y/./x"),
+        ("g#s/../x", "http://a/b/c/g#s/../x"),
+    ];
+    let base = URI::decode(BASE).unwrap();
+    for (r, expected) in cases {
+        let u = base.resolve(&URI::decode(r).unwrap()).unwrap();
+        assert_eq!(u.to_string(), expected, "{}", r);
+    }
+    assert!(URI::decode("/a").unwrap().resolve(&URI::decode("b").unwrap()).is_err());
+}
+
+#[test]
+fn make_relative() {
+    let cases = [
+        ("http://a/b/c/d", "http://a/b/c/e", "e"),
+        ("http://a/b/c/d", "http://a/b/x/y", "../x/y"),
+        ("http://a/b/c/d", "http://a/z", "../../z"),
+        ("http://a/b/c/", "http://a/b/c/", ""),
+        ("http://a/b/c/d", "http://a/b/c/", "./"),
+        ("http://a/b/c/d", "http://a/b/c/d#top", "#top"),
+        ("http://a/b/c/d?x=1", "http://a/b/c/d?x=2", "?x=2"),
+        ("http://a/b/c/d?x=1", "http://a/b/c/d", "d"),
+        ("http://a/b/c/d", "http://a/b/c/a:b", "./a:b"),
+        ("http://a/b/c/d", "http://a/b/c/../e/./f", "../e/f"),
+        ("http://a/b/c/d", "https://a/b/c/d", "https://a/b/c/d"),
+        ("http://a/b/c/d", "http://x:8080/b/c/d?q", "//x:8080/b/c/d?q"),
+    ];
+    for (base, target, expected) in cases {
+        let base = URI::decode(base).unwrap();
+        let target = URI::decode(target).unwrap();
+        let r = base.make_relative(&target);
+        assert_eq!(r.to_string(), expected, "{} -> {}", base, target);
+        assert_eq!(base.resolve(&r).unwrap().to_string(), base.resolve(&target).unwrap().to_string());
+    }
+}
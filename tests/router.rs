@@ -0,0 +1,63 @@
+use uri::path::Path;
+use uri::router::Router;
+use uri::Parser;
+
+fn router() -> Router<&'static str> {
+    let mut r = Router::new();
+    r.insert("/", "root").unwrap();
+    r.insert("/users", "users").unwrap();
+    r.insert("/users/new", "new_user").unwrap();
+    r.insert("/users/:id", "user").unwrap();
+    r.insert("/users/:id/posts/:post", "post").unwrap();
+    r.insert("/users/:id/posts/*rest", "post_files").unwrap();
+    r.insert("/static/*path", "static").unwrap();
+    r.insert("/%3Aliteral", "literal").unwrap();
+    r
+}
+
+#[test]
+fn matching() {
+    let r = router();
+    assert_eq!(r.len(), 8);
+    assert_eq!(*r.at_str("/").unwrap().unwrap().value, "root");
+    assert_eq!(*r.at_str("/users").unwrap().unwrap().value, "users");
+    // 普通段优先于参数
+    let m = r.at_str("/users/new").unwrap().unwrap();
+    assert_eq!((*m.value, m.params.len()), ("new_user", 0));
+    let m = r.at_str("/users/a%20b").unwrap().unwrap();
+    assert_eq!((*m.value, m.params.get("id")), ("user", Some("a b")));
+
+    let m = r.at_str("/users/7/posts/9").unwrap().unwrap();
+    assert_eq!(*m.value, "post");
+    assert_eq!(m.params.iter().collect::<Vec<_>>(), vec![("id", "7"), ("post", "9")]);
+    // 参数匹配失败时回溯到通配
+    let m = r.at_str("/users/7/posts/9/a.png").unwrap().unwrap();
+    assert_eq!((*m.value, m.params.get("rest")), ("post_files", Some("9/a.png")));
+    // 普通段失败时回溯到参数
+    let m = r.at_str("/users/new/posts/1").unwrap().unwrap();
+    assert_eq!((*m.value, m.params.get("id")), ("post", Some("new")));
+
+    let m = r.at(&Path::decode("/static/css/a%2Fb.css").unwrap()).unwrap();
+    assert_eq!(m.params.get("path"), Some("css/a%2Fb.css"));
+    let m = r.at_str("/static/css/a/b%20c.css").unwrap().unwrap();
+    assert_eq!(m.params.get("path"), Some("css/a/b%20c.css"));
+    assert_eq!(*r.at_str("/:literal").unwrap().unwrap().value, "literal");
+
+    assert!(r.at_str("/users/").unwrap().is_none());
+    assert!(r.at_str("/static").unwrap().is_none());
+    assert!(r.at_str("/nope").unwrap().is_none());
+    assert!(r.at_str("relative").unwrap().is_none());
+}
+
+#[test]
+fn insert_errors() {
+    let mut r = router();
+    assert!(r.insert("/users", "dup").is_err());
+    assert!(r.insert("/users/:name", "conflict").is_err());
+    assert!(r.insert("/static/*other", "dup").is_err());
+    assert!(r.insert("/a/*rest/b", "x").is_err());
+    assert!(r.insert("/a/:", "x").is_err());
+    assert!(r.insert("a", "x").is_err());
+    assert!(r.insert("/a b", "x").is_err());
+    assert_eq!(r.len(), 8);
+}
@@ -0,0 +1,45 @@
+#![cfg(feature = "serde")]
+
+use serde::{Deserialize, Serialize};
+use uri::authority::Host;
+use uri::uri::URI;
+
+#[derive(Serialize, Deserialize)]
+struct Config {
+    endpoint: URI,
+    host: Host,
+    #[serde(with = "uri::serde::structured")]
+    upstream: URI,
+}
+
+#[test]
+fn string_form() {
+    let u: URI = serde_json::from_str(r#""https://www.baidu.com:8081/a%20b?q=1#f""#).unwrap();
+    assert_eq!(u.port().unwrap().as_u16(), 8081);
+    assert_eq!(serde_json::to_string(&u).unwrap(), r#""https://www.baidu.com:8081/a%20b?q=1#f""#);
+    assert!(serde_json::from_str::<URI>(r#""http://h:99999""#).is_err());
+}
+
+#[test]
+fn structured_form() {
+    let json = r#"{"endpoint":"http://a/b","host":"[fe03::1]","upstream":{"scheme":"https","host":"www.baidu.com","port":443,"path":"/search","query":[["q","a&b"]],"fragment":"top"}}"#;
+    let c: Config = serde_json::from_str(json).unwrap();
+    assert_eq!(c.upstream.to_string(), "https://www.baidu.com:443/search?q=a%26b#top");
+    assert_eq!(c.host.to_string(), "[fe03::1]");
+    assert_eq!(serde_json::to_string(&c).unwrap(), json);
+
+    let json = r#"{"endpoint":"file:///x","host":"","upstream":{"host":"","path":"/x","query":[]}}"#;
+    let c: Config = serde_json::from_str(json).unwrap();
+    assert_eq!(c.upstream.to_string(), "///x?");
+    assert_eq!(serde_json::to_string(&c).unwrap(), json);
+
+    let json = r#"{"endpoint":"http://h:/","host":"h","upstream":{"scheme":"http","host":"h","port":null,"path":"/"}}"#;
+    let c: Config = serde_json::from_str(json).unwrap();
+    assert_eq!(c.upstream.to_string(), "http://h:/");
+    assert_eq!(serde_json::to_string(&c).unwrap(), json);
+    let json = r#"{"endpoint":"http://h/","host":"h","upstream":{"scheme":"http","host":"h","path":"/"}}"#;
+    assert_eq!(serde_json::from_str::<Config>(json).unwrap().upstream.to_string(), "http://h/");
+
+    let bad = r#"{"endpoint":"http://a/b","host":"h","upstream":{"host":"h","path":"relative"}}"#;
+    assert!(serde_json::from_str::<Config>(bad).is_err());
+}
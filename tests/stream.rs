@@ -0,0 +1,54 @@
+use uri::stream::{Status, TargetParser};
+use uri::target::{RequestTarget, TargetForm};
+
+fn complete(st: Status) -> (RequestTarget, usize) {
+    match st {
+        Status::Complete { target, consumed } => (*target, consumed),
+        Status::NeedMore => panic!("expected complete"),
+    }
+}
+
+#[test]
+fn chunks() {
+    let mut p = TargetParser::new();
+    assert!(matches!(p.feed(b"/a/b").unwrap(), Status::NeedMore));
+    assert!(matches!(p.feed(b"?x=").unwrap(), Status::NeedMore));
+    assert_eq!(p.len(), 7);
+    let (t, n) = complete(p.feed(b"1 HTTP/1.1\r\n").unwrap());
+    assert_eq!((t.as_str(), t.form(), n), ("/a/b?x=1", TargetForm::Origin, 1));
+    assert!(p.is_empty());
+
+    let (t, _) = complete(p.feed(b"http://h:8080/p ").unwrap());
+    assert_eq!((t.as_str(), t.form()), ("http://h:8080/p", TargetForm::Absolute));
+    let (t, _) = complete(p.feed(b"example.com:443 ").unwrap());
+    assert_eq!((t.as_str(), t.form()), ("example.com:443", TargetForm::Authority));
+    let (t, _) = complete(p.feed(b"[::1]:443\r").unwrap());
+    assert_eq!((t.as_str(), t.form()), ("[::1]:443", TargetForm::Authority));
+    let (t, n) = complete(p.feed(b"* ").unwrap());
+    assert_eq!((t.as_str(), t.form(), n), ("*", TargetForm::Asterisk, 1));
+
+    p.feed(b"/end").unwrap();
+    let t = p.finish().unwrap();
+    assert_eq!((t.as_str(), t.form()), ("/end", TargetForm::Origin));
+
+    // origin-form 的路径可以以 "//" 开头, 不是 authority
+    let (t, _) = complete(p.feed(b"//h/a ").unwrap());
+    assert_eq!((t.as_str(), t.form()), ("//h/a", TargetForm::Origin));
+}
+
+#[test]
+fn errors() {
+    let mut p = TargetParser::new();
+    p.set_max_len(8);
+    assert!(matches!(p.feed(b"/1234").unwrap(), Status::NeedMore));
+    let e = p.feed(b"5678").unwrap_err().to_string();
+    assert!(e.contains("exceeds 8"), "{}", e);
+    assert!(p.is_empty());
+
+    let e = p.feed(b"/a\x00").unwrap_err().to_string();
+    assert!(e.contains("0x00 at 2"), "{}", e);
+    assert!(p.feed(b" ").is_err());
+    assert!(p.feed(b"/a#f ").is_err());
+    assert!(p.feed(b"u@h:80 ").is_err());
+    assert!(p.feed(b"a/b ").is_err());
+}
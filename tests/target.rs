@@ -0,0 +1,59 @@
+use uri::target::{RequestTarget, TargetForm, TargetKind};
+use uri::uri::URI;
+use uri::Parser;
+
+#[test]
+fn forms() {
+    let t = RequestTarget::decode("/where?q=now").unwrap();
+    assert_eq!(t.form(), TargetForm::Origin);
+    match t.kind() {
+        TargetKind::Origin { path, query } => {
+            assert_eq!(path.as_str(), "/where");
+            assert_eq!(query.as_ref().unwrap().get("q"), Some("now"));
+        }
+        _ => panic!("expected origin-form"),
+    }
+    // origin-form 的路径可以以 "//" 开头
+    assert_eq!(RequestTarget::decode("//a/b").unwrap().to_string(), "//a/b");
+
+    let t = RequestTarget::decode("http://www.example.org/pub/WWW/TheProject.html").unwrap();
+    assert_eq!(t.form(), TargetForm::Absolute);
+
+    let t = RequestTarget::decode("www.example.com:80").unwrap();
+    assert_eq!(t.form(), TargetForm::Authority);
+    assert_eq!(t.to_string(), "www.example.com:80");
+    assert!(matches!(t.kind(), TargetKind::Authority(a) if a.port().unwrap().as_u16() == 80));
+
+    let t: RequestTarget = "*".parse().unwrap();
+    assert_eq!(t.form(), TargetForm::Asterisk);
+    assert_eq!(RequestTarget::asterisk().as_str(), "*");
+
+    for s in ["", "/a#f", "http://h/#f", "u@h:80", "a/b", "[::1]"] {
+        assert!(RequestTarget::decode(s).is_err(), "{}", s);
+    }
+}
+
+#[test]
+fn conversions() {
+    let t = RequestTarget::decode("/pub/x?a=1").unwrap();
+    let u = t.to_absolute("http", "www.example.org:8080").unwrap();
+    assert_eq!(u.to_string(), "http://www.example.org:8080/pub/x?a=1");
+
+    let (origin, host) = RequestTarget::try_from(u).unwrap().to_origin().unwrap();
+    assert_eq!(origin.to_string(), "/pub/x?a=1");
+    assert_eq!(host.to_string(), "www.example.org:8080");
+
+    let t = RequestTarget::decode("http://u:p@h").unwrap();
+    let (origin, host) = t.to_origin().unwrap();
+    assert_eq!((origin.as_str(), host.as_str()), ("/", "h"));
+    assert!(RequestTarget::decode("*").unwrap().to_origin().is_err());
+
+    let u = RequestTarget::decode("*").unwrap().to_absolute("http", "h").unwrap();
+    assert_eq!(u.to_string(), "http://h");
+    let u = RequestTarget::decode("h:443").unwrap().to_absolute("https", "ignored").unwrap();
+    assert_eq!(u.to_string(), "https://h:443");
+
+    let t = RequestTarget::try_from(URI::decode("http://h/a#frag").unwrap()).unwrap();
+    assert_eq!(t.as_str(), "http://h/a");
+    assert!(RequestTarget::try_from(URI::decode("/a").unwrap()).is_err());
+}
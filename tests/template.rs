@@ -0,0 +1,187 @@
+use uri::template::{Template, Value, Vars};
+use uri::Parser;
+
+// rfc6570 3.2 中使用的变量
+fn vars() -> Vars {
+    let mut vars = Vars::new();
+    vars.insert("count".into(), vec!["one", "two", "three"].into());
+    vars.insert("dom".into(), vec!["example", "com"].into());
+    vars.insert("dub".into(), "me/too".into());
+    vars.insert("hello".into(), "Hello World!".into());
+    vars.insert("half".into(), "50%".into());
+    vars.insert("var".into(), "value".into());
+    vars.insert("who".into(), "fred".into());
+    vars.insert("base".into(), "http://example.com/home/".into());
+    vars.insert("path".into(), "/foo/bar".into());
+    vars.insert("list".into(), vec!["red", "green", "blue"].into());
+    vars.insert("keys".into(), vec![("semi", ";"), ("dot", "."), ("comma", ",")].into());
+    vars.insert("v".into(), "6".into());
+    vars.insert("x".into(), "1024".into());
+    vars.insert("y".into(), "768".into());
+    vars.insert("empty".into(), "".into());
+    vars.insert("empty_keys".into(), Value::Map(Vec::new()));
+    vars
+}
+
+const EXAMPLES: &[(&str, &str)] = &[
+    // 3.2.1
+    ("{count}", "one,two,three"),
+    ("{count*}", "one,two,three"),
+    ("{/count}", "/one,two,three"),
+    ("{/count*}", "/one/two/three"),
+    ("{;count}", ";count=one,two,three"),
+    ("{;count*}", ";count=one;count=two;count=three"),
+    ("{?count}", "?count=one,two,three"),
+    ("{?count*}", "?count=one&count=two&count=three"),
+    ("{&count*}", "&count=one&count=two&count=three"),
+    // 3.2.2
+    ("{var}", "value"),
+    ("{hello}", "Hello%20World%21"),
+    ("{half}", "50%25"),
+    ("O{empty}X", "OX"),
+    ("O{undef}X", "OX"),
+    ("{x,y}", "1024,768"),
+    ("{x,hello,y}", "1024,Hello%20World%21,768"),
+    ("?{x,empty}", "?1024,"),
+    ("?{x,undef}", "?1024"),
+    ("?{undef,y}", "?768"),
+    ("{var:3}", "val"),
+    ("{var:30}", "value"),
+    ("{list}", "red,green,blue"),
+    ("{list*}", "red,green,blue"),
+    ("{keys}", "semi,%3B,dot,.,comma,%2C"),
+    ("{keys*}", "semi=%3B,dot=.,comma=%2C"),
+    // 3.2.3
+    ("{+var}", "value"),
+    ("{+hello}", "Hello%20World!"),
+    ("{+half}", "50%25"),
+    ("{base}index", "http%3A%2F%2Fexample.com%2Fhome%2Findex"),
+    ("{+base}index", "http://example.com/home/index"),
+    ("O{+empty}X", "OX"),
+    ("O{+undef}X", "OX"),
+    ("{+path}/here", "/foo/bar/here"),
+    ("here?ref={+path}", "here?ref=/foo/bar"),
+    ("up{+path}{var}/here", "up/foo/barvalue/here"),
+    ("{+x,hello,y}", "1024,Hello%20World!,768"),
+    ("{+path,x}/here", "/foo/bar,1024/here"),
+    ("{+path:6}/here", "/foo/b/here"),
+    ("{+list}", "red,green,blue"),
+    ("{+list*}", "red,green,blue"),
+    ("{+keys}", "semi,;,dot,.,comma,,"),
+    ("{+keys*}", "semi=;,dot=.,comma=,"),
+    // 3.2.4
+    ("{#var}", "#value"),
+    ("{#hello}", "#Hello%20World!"),
+    ("{#half}", "#50%25"),
+    ("foo{#empty}", "foo#"),
+    ("foo{#undef}", "foo"),
+    ("{#x,hello,y}", "#1024,Hello%20World!,768"),
+    ("{#path,x}/here", "#/foo/bar,1024/here"),
+    ("{#path:6}/here", "#/foo/b/here"),
+    ("{#list}", "#red,green,blue"),
+    ("{#list*}", "#red,green,blue"),
+    ("{#keys}", "#semi,;,dot,.,comma,,"),
+    ("{#keys*}", "#semi=;,dot=.,comma=,"),
+    // 3.2.5
+    ("{.who}", ".fred"),
+    ("{.who,who}", ".fred.fred"),
+    ("{.half,who}", ".50%25.fred"),
+    ("www{.dom*}", "www.example.com"),
+    ("X{.var}", "X.value"),
+    ("X{.empty}", "X."),
+    ("X{.undef}", "X"),
+    ("X{.var:3}", "X.val"),
+    ("X{.list}", "X.red,green,blue"),
+    ("X{.list*}", "X.red.green.blue"),
+    ("X{.keys}", "X.semi,%3B,dot,.,comma,%2C"),
+    ("X{.keys*}", "X.semi=%3B.dot=..comma=%2C"),
+    ("X{.empty_keys}", "X"),
+    ("X{.empty_keys*}", "X"),
+    // 3.2.6
+    ("{/who}", "/fred"),
+    ("{/who,who}", "/fred/fred"),
+    ("{/half,who}", "/50%25/fred"),
+    ("{/who,dub}", "/fred/me%2Ftoo"),
+    ("{/var}", "/value"),
+    ("{/var,empty}", "/value/"),
+    ("{/var,undef}", "/value"),
+    ("{/var,x}/here", "/value/1024/here"),
+    ("{/var:1,var}", "/v/value"),
+    ("{/list}", "/red,green,blue"),
+    ("{/list*}", "/red/green/blue"),
+    ("{/list*,path:4}", "/red/green/blue/%2Ffoo"),
+    ("{/keys}", "/semi,%3B,dot,.,comma,%2C"),
+    ("{/keys*}", "/semi=%3B/dot=./comma=%2C"),
+    // 3.2.7
+    ("{;who}", ";who=fred"),
+    ("{;half}", ";half=50%25"),
+    ("{;empty}", ";empty"),
+    ("{;v,empty,who}", ";v=6;empty;who=fred"),
+    ("{;v,bar,who}", ";v=6;who=fred"),
+    ("{;x,y}", ";x=1024;y=768"),
+    ("{;x,y,empty}", ";x=1024;y=768;empty"),
+    ("{;x,y,undef}", ";x=1024;y=768"),
+    ("{;hello:5}", ";hello=Hello"),
+    ("{;list}", ";list=red,green,blue"),
+    ("{;list*}", ";list=red;list=green;list=blue"),
+    ("{;keys}", ";keys=semi,%3B,dot,.,comma,%2C"),
+    ("{;keys*}", ";semi=%3B;dot=.;comma=%2C"),
+    // 3.2.8
+    ("{?who}", "?who=fred"),
+    ("{?half}", "?half=50%25"),
+    ("{?x,y}", "?x=1024&y=768"),
+    ("{?x,y,empty}", "?x=1024&y=768&empty="),
+    ("{?x,y,undef}", "?x=1024&y=768"),
+    ("{?var:3}", "?var=val"),
+    ("{?list}", "?list=red,green,blue"),
+    ("{?list*}", "?list=red&list=green&list=blue"),
+    ("{?keys}", "?keys=semi,%3B,dot,.,comma,%2C"),
+    ("{?keys*}", "?semi=%3B&dot=.&comma=%2C"),
+    // 3.2.9
+    ("{&who}", "&who=fred"),
+    ("{&half}", "&half=50%25"),
+    ("?fixed=yes{&x}", "?fixed=yes&x=1024"),
+    ("{&x,y,empty}", "&x=1024&y=768&empty="),
+    ("{&var:3}", "&var=val"),
+    ("{&list}", "&list=red,green,blue"),
+    ("{&list*}", "&list=red&list=green&list=blue"),
+    ("{&keys}", "&keys=semi,%3B,dot,.,comma,%2C"),
+    ("{&keys*}", "&semi=%3B&dot=.&comma=%2C"),
+];
+
+#[test]
+fn rfc_examples() {
+    let vars = vars();
+    for (t, expected) in EXAMPLES {
+        let tpl = Template::decode(t).unwrap();
+        assert_eq!(tpl.expand(&vars), *expected, "{}", t);
+    }
+}
+
+#[test]
+fn api_templates() {
+    let tpl: Template = "https://api.example.com/repos/{owner}/{repo}{?page,per_page}".parse().unwrap();
+    assert_eq!(tpl.variables(), vec!["owner", "repo", "page", "per_page"]);
+    let mut vars = Vars::new();
+    vars.insert("owner".into(), "rs common".into());
+    vars.insert("repo".into(), "uri".into());
+    vars.insert("page".into(), "2".into());
+    let u = tpl.expand_uri(&vars).unwrap();
+    assert_eq!(u.to_string(), "https://api.example.com/repos/rs%20common/uri?page=2");
+    assert_eq!(u.query().unwrap().get("page"), Some("2"));
+
+    // 非 ascii 字面量与值都会编码
+    let tpl = Template::decode("/代{/path*}").unwrap();
+    vars.insert("path".into(), vec!["a b", "代"].into());
+    assert_eq!(tpl.expand(&vars), "/%E4%BB%A3/a%20b/%E4%BB%A3");
+    assert_eq!(tpl.to_string(), "/代{/path*}");
+}
+
+#[test]
+fn parse_errors() {
+    for t in ["{var", "{}", "{=var}", "{!var}", "{var:0}", "{var:10000}", "{va r}", "{.var.}", "a}b", "a b", "%zz", "{a..b}", "it's/{x}"] {
+        assert!(Template::decode(t).is_err(), "{}", t);
+    }
+    assert!(Template::decode("{a.b,c%20d,_e:9999}").is_ok());
+    assert!(Template::decode("it%27s/{x}").is_ok());
+}
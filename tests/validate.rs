@@ -0,0 +1,46 @@
+use uri::authority::{Authority, Host, Port, UserInfo};
+use uri::path::Path;
+use uri::scheme::Scheme;
+use uri::uri::URI;
+use uri::validate::{ErrorKind, ValidationError};
+
+#[test]
+fn positions() {
+    assert!(URI::is_valid("http://u@[v1.x]:80/a%20b?q#f"));
+    assert!(!URI::is_valid("http://h/a b"));
+    let e = URI::validate_str("http://h/a b").unwrap_err();
+    assert_eq!(e, ValidationError::new(10, ErrorKind::InvalidChar(b' ')));
+    let e = URI::validate_str("http://h/?q=%4").unwrap_err();
+    assert_eq!((e.position(), e.kind()), (12, ErrorKind::InvalidPercent));
+    let e = URI::validate_str("http://u@h:8x/").unwrap_err();
+    assert_eq!((e.position(), e.kind()), (12, ErrorKind::InvalidPort));
+    let e = URI::validate_str("http://[::1/").unwrap_err();
+    assert_eq!(e.kind(), ErrorKind::InvalidHost);
+    let e = URI::validate_str("s:/a#b#c").unwrap_err();
+    assert_eq!((e.position(), e.kind()), (6, ErrorKind::UnexpectedHash));
+    let e = URI::validate_str("1http://h").unwrap_err();
+    assert_eq!((e.position(), e.kind()), (0, ErrorKind::InvalidScheme));
+    assert_eq!(e.to_string(), "invalid scheme at 0");
+    let e = URI::validate_str("http://h/代").unwrap_err();
+    assert_eq!(e.to_string(), "invalid byte 0xE4 at 9");
+}
+
+#[test]
+fn components() {
+    assert!(Scheme::validate("svn+ssh").is_ok());
+    assert!(Scheme::validate("").is_err());
+    assert!(UserInfo::validate("u:p:%41").is_ok());
+    assert!(UserInfo::validate("u@").is_err());
+    assert!(Host::validate("example.com").is_ok());
+    assert!(Host::validate("[2001:db8::7]").is_ok());
+    assert!(Host::validate("[v7.x]").is_ok());
+    assert!(Host::validate("").is_ok());
+    assert_eq!(Host::validate("[zz]").unwrap_err().position(), 1);
+    assert!(Port::validate("65535").is_ok());
+    assert_eq!(Port::validate("65536").unwrap_err().position(), 4);
+    assert!(Authority::validate("u@[::1]:80").is_ok());
+    assert!(Authority::validate("h:").is_ok());
+    assert_eq!(Authority::validate("u@[::1]x").unwrap_err().position(), 7);
+    assert!(Path::validate("/a/b:c@d").is_ok());
+    assert_eq!(Path::validate("/a?b").unwrap_err().position(), 2);
+}